@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use advent_of_code_2024::{input::Input, *};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// Benchmarks parsing and both parts of a day against its puzzle input, if
+/// there is one, with the parameters of its sidecar
+fn bench_day<S: Solution>(c: &mut Criterion) {
+    let file = Input::of_day(S::DAY);
+    let Ok(input) = file.read() else {
+        return;
+    };
+    let params = file.params().unwrap().resolve(S::PARAMS).unwrap();
+    let parsed = S::parse_with(&input, &params).unwrap();
+
+    let mut group = c.benchmark_group(format!("day_{}", S::DAY));
+    group
+        .sample_size(10)
+        .warm_up_time(Duration::from_millis(500));
+    group.bench_function("parse", |b| b.iter(|| S::parse_with(&input, &params)));
+    group.bench_function("part_1", |b| b.iter(|| S::part_1(&parsed)));
+    group.bench_function("part_2", |b| b.iter(|| S::part_2(&parsed)));
+    group.finish();
+}
+
+criterion_group!(
+    days,
+    bench_day::<day_1::Day1>,
+    bench_day::<day_2::Day2>,
+    bench_day::<day_3::Day3>,
+    bench_day::<day_4::Day4>,
+    bench_day::<day_5::Day5>,
+    bench_day::<day_6::Day6>,
+    bench_day::<day_7::Day7>,
+    bench_day::<day_8::Day8>,
+    bench_day::<day_9::Day9>,
+    bench_day::<day_10::Day10>,
+    bench_day::<day_11::Day11>,
+    bench_day::<day_12::Day12>,
+    bench_day::<day_13::Day13>,
+    bench_day::<day_14::Day14>,
+    bench_day::<day_15::Day15>,
+    bench_day::<day_16::Day16>,
+    bench_day::<day_17::Day17>,
+    bench_day::<day_18::Day18>,
+    bench_day::<day_19::Day19>,
+    bench_day::<day_20::Day20>,
+    bench_day::<day_21::Day21>,
+    bench_day::<day_22::Day22>,
+    bench_day::<day_23::Day23>,
+    bench_day::<day_24::Day24>,
+    bench_day::<day_25::Day25>,
+);
+criterion_main!(days);
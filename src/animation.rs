@@ -0,0 +1,242 @@
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    image::{Image, Rgb},
+    render::Canvas,
+    Error, Result,
+};
+
+/// Which steps of a simulation become frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frames {
+    pub first: usize,
+    /// The last step drawn, or `None` to draw until the simulation ends
+    pub last: Option<usize>,
+    /// Draw one step out of this many
+    pub every: usize,
+}
+
+impl Default for Frames {
+    fn default() -> Self {
+        Frames {
+            first: 0,
+            last: None,
+            every: 1,
+        }
+    }
+}
+
+impl Frames {
+    /// Reads a `FIRST..LAST` range of steps, both included, where either end
+    /// may be left out
+    pub fn parse_range(range: &str) -> std::result::Result<(usize, Option<usize>), String> {
+        let invalid = || format!("expected `FIRST..LAST`, got `{range}`");
+        let (first, last) = range.split_once("..").ok_or_else(invalid)?;
+        let first = match first.trim() {
+            "" => 0,
+            first => first.parse().map_err(|_| invalid())?,
+        };
+        let last = match last.trim() {
+            "" => None,
+            last => Some(last.parse().map_err(|_| invalid())?),
+        };
+        match last {
+            Some(last) if last < first => Err(format!("`{range}` ends before it starts")),
+            _ => Ok((first, last)),
+        }
+    }
+
+    fn contains(&self, step: usize) -> bool {
+        step >= self.first
+            && self.last.is_none_or(|last| step <= last)
+            && (step - self.first).is_multiple_of(self.every)
+    }
+}
+
+/// Where the frames go: an animated GIF, or a directory of numbered images
+enum Output {
+    Gif {
+        path: PathBuf,
+        /// Time each frame shows for, in hundredths of a second
+        delay: u16,
+        encoder: Option<gif::Encoder<BufWriter<File>>>,
+    },
+    Pngs {
+        directory: PathBuf,
+    },
+}
+
+/// Collects the frames of a simulation as it runs, writing each one out as
+/// soon as it is drawn
+pub struct Recorder {
+    frames: Frames,
+    scale: usize,
+    output: Output,
+    step: usize,
+    written: usize,
+    error: Option<Error>,
+}
+
+impl Recorder {
+    /// Records to `path`: a GIF when it ends in `.gif`, otherwise a directory
+    /// of PNG frames. `delay` is the time between GIF frames in milliseconds.
+    pub fn new(path: &Path, frames: Frames, scale: usize, delay: u64) -> Self {
+        let output = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("gif") => Output::Gif {
+                path: path.to_owned(),
+                delay: (delay / 10).clamp(1, u16::MAX as u64) as u16,
+                encoder: None,
+            },
+            _ => Output::Pngs {
+                directory: path.to_owned(),
+            },
+        };
+        Recorder {
+            frames,
+            scale,
+            output,
+            step: 0,
+            written: 0,
+            error: None,
+        }
+    }
+
+    /// Moves on to the next step of the simulation, calling `draw` only when
+    /// the step is one of the frames
+    pub fn frame(&mut self, draw: impl FnOnce() -> Canvas) {
+        let step = self.step;
+        self.step += 1;
+        if self.error.is_some() || !self.frames.contains(step) {
+            return;
+        }
+        let image = draw().to_image(self.scale);
+        match self.write(step, &image) {
+            Ok(()) => self.written += 1,
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    /// Whether no step to come will be drawn, so the simulation can stop
+    pub fn is_done(&self) -> bool {
+        self.error.is_some() || self.frames.last.is_some_and(|last| self.step > last)
+    }
+
+    /// Finishes the output, returning how many frames were written
+    pub fn finish(self) -> Result<usize> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if let Output::Gif {
+            path,
+            encoder: Some(encoder),
+            ..
+        } = self.output
+        {
+            encoder
+                .into_inner()
+                .map_err(io::Error::other)
+                .and_then(|mut file| file.flush())
+                .map_err(|source| Error::Io { path, source })?;
+        }
+        Ok(self.written)
+    }
+
+    fn write(&mut self, step: usize, image: &Image) -> Result<()> {
+        match &mut self.output {
+            Output::Gif {
+                path,
+                delay,
+                encoder,
+            } => {
+                let io_error = |source| Error::Io {
+                    path: path.clone(),
+                    source,
+                };
+                let (width, height) = (image.width() as u16, image.height() as u16);
+                let encoder = match encoder {
+                    Some(encoder) => encoder,
+                    None => {
+                        let file = File::create(&*path).map_err(io_error)?;
+                        let created = gif::Encoder::new(BufWriter::new(file), width, height, &[])
+                            .and_then(|mut created| {
+                                created.set_repeat(gif::Repeat::Infinite)?;
+                                Ok(created)
+                            })
+                            .map_err(|e| io_error(io::Error::other(e)))?;
+                        encoder.insert(created)
+                    }
+                };
+                let mut frame = gif_frame(image);
+                frame.delay = *delay;
+                encoder
+                    .write_frame(&frame)
+                    .map_err(|e| io_error(io::Error::other(e)))
+            }
+            Output::Pngs { directory } => {
+                create_dir_all(&*directory).map_err(|source| Error::Io {
+                    path: directory.clone(),
+                    source,
+                })?;
+                image.save(&directory.join(format!("frame_{step:05}.png")))
+            }
+        }
+    }
+}
+
+/// A GIF frame of `image`, with a palette of its own colours when they fit
+fn gif_frame(image: &Image) -> gif::Frame<'static> {
+    let (width, height) = (image.width() as u16, image.height() as u16);
+    let mut palette: HashMap<Rgb, u8> = HashMap::new();
+    let mut indices = Vec::with_capacity(image.pixels().len());
+    for &pixel in image.pixels() {
+        let next = palette.len();
+        match palette.get(&pixel) {
+            Some(&index) => indices.push(index),
+            None if next < 256 => {
+                palette.insert(pixel, next as u8);
+                indices.push(next as u8);
+            }
+            // Too many colours for a palette, so let the encoder pick one
+            None => {
+                let mut bytes = Vec::new();
+                for &Rgb(r, g, b) in image.pixels() {
+                    bytes.extend([r, g, b]);
+                }
+                return gif::Frame::from_rgb_speed(width, height, &bytes, 10);
+            }
+        }
+    }
+    let mut colours = vec![0; palette.len() * 3];
+    for (Rgb(r, g, b), index) in palette {
+        let index = index as usize * 3;
+        colours[index..index + 3].copy_from_slice(&[r, g, b]);
+    }
+    gif::Frame::from_palette_pixels(width, height, indices, colours, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_ranges() {
+        assert_eq!(Frames::parse_range("10..20"), Ok((10, Some(20))));
+        assert_eq!(Frames::parse_range("..20"), Ok((0, Some(20))));
+        assert_eq!(Frames::parse_range("5.."), Ok((5, None)));
+        assert!(Frames::parse_range("20..10").is_err());
+        assert!(Frames::parse_range("20").is_err());
+
+        let frames = Frames {
+            first: 10,
+            last: Some(20),
+            every: 5,
+        };
+        let steps: Vec<usize> = (0..30).filter(|&step| frames.contains(step)).collect();
+        assert_eq!(steps, [10, 15, 20]);
+    }
+}
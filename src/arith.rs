@@ -0,0 +1,118 @@
+use std::{
+    any::Any,
+    fmt::Display,
+    ops::{Add, Mul, Sub},
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+};
+
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub, One};
+
+use crate::{Error, Result};
+
+/// Integer arithmetic for the solvers whose numbers may outgrow their type.
+/// Without the `checked` feature, these are the plain operators, which wrap
+/// in release builds. With it, an overflow stops the solver, and
+/// [`catch_overflow`] reports it as an [`Error::Overflow`].
+///
+/// Both operands are borrowed, so the same code runs on primitive integers
+/// and on the big integers of the `bigint` feature.
+pub fn add<T>(a: &T, b: &T) -> T
+where
+    T: CheckedAdd + Display,
+    for<'a> &'a T: Add<&'a T, Output = T>,
+{
+    match cfg!(feature = "checked") {
+        true => a
+            .checked_add(b)
+            .unwrap_or_else(|| overflow(format!("{a} + {b}"))),
+        false => a + b,
+    }
+}
+
+pub fn sub<T>(a: &T, b: &T) -> T
+where
+    T: CheckedSub + Display,
+    for<'a> &'a T: Sub<&'a T, Output = T>,
+{
+    match cfg!(feature = "checked") {
+        true => a
+            .checked_sub(b)
+            .unwrap_or_else(|| overflow(format!("{a} - {b}"))),
+        false => a - b,
+    }
+}
+
+pub fn mul<T>(a: &T, b: &T) -> T
+where
+    T: CheckedMul + Display,
+    for<'a> &'a T: Mul<&'a T, Output = T>,
+{
+    match cfg!(feature = "checked") {
+        true => a
+            .checked_mul(b)
+            .unwrap_or_else(|| overflow(format!("{a} * {b}"))),
+        false => a * b,
+    }
+}
+
+/// `base` to the power of `exponent`
+pub fn pow<T>(base: &T, exponent: u32) -> T
+where
+    T: Clone + One + CheckedMul + Display,
+{
+    match cfg!(feature = "checked") {
+        true => num_traits::checked_pow(base.clone(), exponent as usize)
+            .unwrap_or_else(|| overflow(format!("{base}^{exponent}"))),
+        false => num_traits::pow(base.clone(), exponent as usize),
+    }
+}
+
+/// What a solver was computing when its numbers overflowed
+#[derive(Debug)]
+struct Overflow {
+    operation: String,
+}
+
+/// Stops the solver, unwinding up to [`catch_overflow`]. The panic hook is
+/// not called, so nothing is printed on the way.
+fn overflow<T>(operation: String) -> T {
+    resume_unwind(Box::new(Overflow { operation }))
+}
+
+/// Runs `solve`, returning the overflow it stopped at as an error. Other
+/// panics go on unwinding.
+pub fn catch_overflow<T>(solve: impl FnOnce() -> T) -> Result<T> {
+    catch_unwind(AssertUnwindSafe(solve)).map_err(|payload: Box<dyn Any + Send>| {
+        match payload.downcast::<Overflow>() {
+            Ok(overflow) => Error::Overflow {
+                operation: overflow.operation,
+            },
+            Err(payload) => resume_unwind(payload),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        assert_eq!(add(&2_u64, &3), 5);
+        assert_eq!(sub(&2_i64, &3), -1);
+        assert_eq!(mul(&2024_u64, &3), 6072);
+        assert_eq!(pow(&10_u64, 19), 10_000_000_000_000_000_000);
+        assert_eq!(catch_overflow(|| add(&1_u8, &2)).unwrap(), 3);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn overflows_are_errors() {
+        let error = catch_overflow(|| mul(&u64::MAX, &2024)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("arithmetic overflow computing {} * 2024", u64::MAX)
+        );
+        assert!(catch_overflow(|| pow(&10_u64, 20)).is_err());
+    }
+}
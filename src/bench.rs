@@ -0,0 +1,147 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string, write},
+    hint::black_box,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{params::Params, Error, Result, Solution};
+
+/// How long to run a solver before sampling, and how many samples to take
+#[derive(Debug, Clone, Copy)]
+pub struct Sampling {
+    pub warm_up: Duration,
+    pub samples: usize,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Sampling {
+            warm_up: Duration::from_millis(500),
+            samples: 10,
+        }
+    }
+}
+
+/// Summary of the samples of one measured step, in nanoseconds
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Stats {
+    pub min: u64,
+    pub median: u64,
+    pub mean: u64,
+    pub samples: usize,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let nanos = |duration: Duration| duration.as_nanos() as u64;
+        Stats {
+            min: nanos(samples[0]),
+            median: nanos(samples[samples.len() / 2]),
+            mean: nanos(samples.iter().sum::<Duration>() / samples.len() as u32),
+            samples: samples.len(),
+        }
+    }
+
+    pub fn median(&self) -> Duration {
+        Duration::from_nanos(self.median)
+    }
+
+    /// Change of the median against the same step of a baseline, in percent,
+    /// unless the baseline took no measurable time
+    pub fn change(&self, baseline: &Stats) -> Option<f64> {
+        let before = baseline.median as f64;
+        (baseline.median > 0).then(|| (self.median as f64 - before) / before * 100.0)
+    }
+}
+
+/// Timings of a day, with parsing measured apart from the parts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Measurement {
+    pub parse: Stats,
+    pub part_1: Stats,
+    pub part_2: Stats,
+}
+
+/// Warms `step` up, then times it once per sample
+fn sample<T>(sampling: &Sampling, mut step: impl FnMut() -> T) -> Stats {
+    let start = Instant::now();
+    loop {
+        black_box(step());
+        if start.elapsed() >= sampling.warm_up {
+            break;
+        }
+    }
+
+    let samples = (0..sampling.samples.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(step());
+            start.elapsed()
+        })
+        .collect();
+    Stats::from_samples(samples)
+}
+
+/// Times the day on `input`, solved with `params` as when it is run
+pub fn measure<S: Solution>(
+    input: &str,
+    params: &Params,
+    sampling: &Sampling,
+) -> Result<Measurement> {
+    let params = params.resolve(S::PARAMS)?;
+    let parsed = S::parse_with(input, &params)?;
+    Ok(Measurement {
+        parse: sample(sampling, || S::parse_with(input, &params)),
+        part_1: sample(sampling, || S::part_1(&parsed)),
+        part_2: sample(sampling, || S::part_2(&parsed)),
+    })
+}
+
+/// Saved measurements of every benchmarked day, to compare later runs against
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    #[serde(default)]
+    pub days: BTreeMap<String, Measurement>,
+}
+
+impl Baseline {
+    /// Where the baseline of the given name is kept
+    pub fn path(name: &str) -> PathBuf {
+        PathBuf::from(format!("./target/aoc-bench/{name}.toml"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        toml::from_str(&text).map_err(|e| Error::Invalid {
+            path: path.to_owned(),
+            reason: e.message().to_owned(),
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let io_error = |source| Error::Io {
+            path: path.to_owned(),
+            source,
+        };
+        if let Some(folder) = path.parent() {
+            create_dir_all(folder).map_err(io_error)?;
+        }
+        write(path, toml::to_string(self).unwrap()).map_err(io_error)
+    }
+
+    pub fn get(&self, day: u8) -> Option<&Measurement> {
+        self.days.get(&format!("day_{day}"))
+    }
+
+    pub fn insert(&mut self, day: u8, measurement: Measurement) {
+        self.days.insert(format!("day_{day}"), measurement);
+    }
+}
@@ -1,33 +1,75 @@
-mod utilities;
-use utilities::read_lines;
+use crate::{
+    generate::{Generated, Generator},
+    parse::Source,
+    Answer, Result, Solution,
+};
 
-fn main() {
-    let mut num_as: Vec<i32> = Vec::new();
-    let mut num_bs: Vec<i32> = Vec::new();
-    if let Ok(lines) = read_lines("./input/day_1.txt") {
-        for line in lines.flatten() {
-            if let Some((str_a, str_b)) = line.split_once("   ") {
-                num_as.push(str_a.parse::<i32>().unwrap());
-                num_bs.push(str_b.parse::<i32>().unwrap());
-            }
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = (Vec<i32>, Vec<i32>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_input(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(&input.0, &input.1).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(&input.0, &input.1).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let left: Vec<u32> = (0..generator.size())
+            .map(|_| generator.number(10000..100000))
+            .collect();
+        let mut input = String::new();
+        for &a in &left {
+            // Half the right numbers come from the left list, so that the
+            // second part finds similarities
+            let b = match generator.chance(0.5) {
+                true => *generator.pick(&left),
+                false => generator.number(10000..100000),
+            };
+            input.push_str(&format!("{a}   {b}\n"));
         }
+        Generated::new(input)
     }
-    println!("List 1 length: {}", num_as.len());
-    println!("List 2 length: {}", num_bs.len());
-    
+}
+
+fn part_1(num_as: &[i32], num_bs: &[i32]) -> i32 {
+    let mut num_as = num_as.to_vec();
+    let mut num_bs = num_bs.to_vec();
     num_as.sort();
     num_bs.sort();
     let mut distances: Vec<i32> = Vec::new();
     for i in 0..num_as.len() {
         distances.push(i32::abs(num_as[i] - num_bs[i]));
     }
-    println!("Part A answer: {}", distances.iter().sum::<i32>());
+    distances.iter().sum::<i32>()
+}
 
+fn part_2(num_as: &[i32], num_bs: &[i32]) -> i32 {
     let mut scores: Vec<i32> = Vec::new();
-    for i in 0..num_as.len() {
-        let a = num_as[i];
+    for &a in num_as {
         let c = num_bs.iter().filter(|&b| *b == a).count() as i32;
         scores.push(a * c);
     }
-    println!("Part B answer: {}", scores.iter().sum::<i32>());
+    scores.iter().sum::<i32>()
+}
+
+fn read_input(input: &str) -> Result<(Vec<i32>, Vec<i32>)> {
+    let source = Source::new(input);
+    let mut num_as: Vec<i32> = Vec::new();
+    let mut num_bs: Vec<i32> = Vec::new();
+    for line in source.lines() {
+        let (str_a, str_b) = source.split_once(line, "   ")?;
+        num_as.push(source.number(str_a)?);
+        num_bs.push(source.number(str_b)?);
+    }
+    Ok((num_as, num_bs))
 }
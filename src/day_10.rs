@@ -1,86 +1,148 @@
-use std::{collections::VecDeque, fs::read_to_string};
-
 use itertools::Itertools;
 
-type HikeMap = Vec<Vec<u32>>;
+use crate::{
+    generate::{Generated, Generator},
+    grid::Grid,
+    parse::Source,
+    point::Point,
+    render::{Canvas, Color},
+    search::{self, Explored},
+    Answer, Part, Result, Solution,
+};
+
+type HikeMap = Grid<u32>;
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = HikeMap;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Source::new(input).grid_with(input, "a height", |c| c.to_digit(10))
+    }
 
-fn main() {
-    let test = read_map("./input/day_10.test.txt");
-    let input = read_map("./input/day_10.txt");
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
+    }
 
-    println!("First part test value: {} == 36", part_1(&test));
-    println!("Second part test value: {} == 81", part_2(&test));
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
+    }
 
-    println!("First part answer: {}", part_1(&input));
-    println!("Second part answer: {}", part_2(&input));
+    fn generate(generator: &mut Generator) -> Generated {
+        let side = generator.size() + 4;
+        let mut map = Grid::new(side, side, 0);
+        for point in map.positions().collect_vec() {
+            map[point] = generator.number(0..=9);
+        }
+        // Climbs from 0 to 9 laid over the noise, so that there are trails
+        for _ in 0..side / 2 {
+            let mut point = Point::new(
+                generator.number(0..side as i32),
+                generator.number(0..side as i32),
+            );
+            for height in 0..=9 {
+                map[point] = height;
+                let next = map.neighbors(point).map(|(next, _)| next).collect_vec();
+                point = *generator.pick(&next);
+            }
+        }
+        Generated::new(map.render(|&height| char::from_digit(height, 10).unwrap()) + "\n")
+    }
+
+    fn render(input: &Self::Input, part: Part) -> Option<Canvas> {
+        let trails = visit_trails(input);
+        let canvas = Canvas::new(input.map(|&height| char::from_digit(height, 10).unwrap()));
+        let canvas = match part {
+            Part::One => canvas.highlight(
+                "reached summit",
+                Color::Red,
+                trails.iter().flat_map(|trails| summits(input, trails)),
+            ),
+            Part::Two => canvas.highlight(
+                "trail",
+                Color::Green,
+                trails.iter().flat_map(|trails| {
+                    trails.on_optimal_paths(summits(input, trails).collect::<Vec<_>>())
+                }),
+            ),
+        };
+        Some(canvas.highlight("trailhead", Color::Cyan, find_trailheads(input)))
+    }
 }
 
 fn part_1(map: &HikeMap) -> usize {
     visit_trails(map)
         .iter()
-        .map(|head| head.iter().unique().count())
+        .map(|trails| summits(map, trails).count())
         .sum()
 }
 
 fn part_2(map: &HikeMap) -> usize {
-    visit_trails(map).iter().map(|head| head.len()).sum()
+    visit_trails(map)
+        .iter()
+        .map(|trails| {
+            summits(map, trails)
+                .map(|summit| trails.count_paths_to(&summit))
+                .sum::<usize>()
+        })
+        .sum()
 }
 
-fn find_trailheads(map: &HikeMap) -> Vec<(usize, usize)> {
-    let length = map.len();
-    (0..length)
-        .map(|y| (0..length).map(move |x| (y, x)))
-        .flatten()
-        .filter(|&(x, y)| map[y][x] == 0)
-        .collect()
+fn find_trailheads(map: &HikeMap) -> Vec<Point> {
+    map.find_all(|&height| height == 0).collect()
 }
 
-fn neighbors(map: &HikeMap, position: (usize, usize)) -> Vec<(usize, usize)> {
-    let (x, y) = position;
-    let (a, b): (i32, i32) = (x as i32, y as i32);
-    [(a + 1, b), (a - 1, b), (a, b + 1), (a, b - 1)]
-        .iter()
-        .filter_map(|&(i, j)| match map.get(j as usize) {
-            Some(row) => match row.get(i as usize) {
-                Some(_) => Some((i as usize, j as usize)),
-                None => None,
-            },
-            None => None,
-        })
-        .filter(|&(i, j)| u32::abs_diff(map[y][x], map[j][i]) == 1 && map[y][x] < map[j][i])
+/// Neighbours one step higher up
+fn neighbors(map: &HikeMap, point: Point) -> Vec<Point> {
+    let height = map[point];
+    map.neighbors(point)
+        .filter(|(_, &other)| other == height + 1)
+        .map(|(other, _)| other)
         .collect()
 }
 
-fn visit_trails(map: &HikeMap) -> Vec<Vec<(usize, usize)>> {
+/// The tiles of height 9 the trails of one trailhead reach
+fn summits<'a>(
+    map: &'a HikeMap,
+    trails: &'a Explored<Point, usize>,
+) -> impl Iterator<Item = Point> + 'a {
+    trails
+        .distances()
+        .keys()
+        .copied()
+        .filter(|&point| map[point] == 9)
+}
+
+/// Every trail out of each trailhead. Trails climb one height at a time, so
+/// each of them is a shortest path to its end.
+fn visit_trails(map: &HikeMap) -> Vec<Explored<Point, usize>> {
     find_trailheads(map)
         .iter()
-        .map(|&start| {
-            let mut queue = VecDeque::from([Vec::from([start])]);
-            let mut visited: Vec<(usize, usize)> = Vec::new();
-            while let Some(trail) = queue.pop_front() {
-                let &(x, y) = trail.last().unwrap();
-
-                if map[y][x] == 9 {
-                    visited.push((x, y))
-                } else {
-                    queue.extend(
-                        neighbors(map, (x, y))
-                            .iter()
-                            .map(|p| trail.iter().chain([p]).cloned().collect()),
-                    );
-                }
-            }
-
-            visited
-        })
+        .map(|&start| search::bfs([start], |&point| neighbors(map, point), |_| false))
         .collect()
 }
 
-fn read_map(file_path: &str) -> HikeMap {
-    read_to_string(file_path)
-        .unwrap()
-        .trim()
-        .split("\n")
-        .map(|row| row.chars().map(|c| c.to_digit(10)).flatten().collect())
-        .collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            part_1(&Day10::parse(&read_example(10, "test")).unwrap()),
+            36
+        );
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(
+            part_2(&Day10::parse(&read_example(10, "test")).unwrap()),
+            81
+        );
+    }
 }
@@ -1,77 +1,144 @@
-use std::{collections::HashMap, time::Instant};
+use std::{collections::HashMap, iter::repeat};
 
-use cached::proc_macro::cached;
+use itertools::Itertools;
+use num_traits::{One, Zero};
 
+use crate::{
+    arith,
+    digits::Digits,
+    generate::{Generated, Generator},
+    parse::Source,
+    Answer, Result, Solution,
+};
+
+/// Numbers engraved on stones, and counts of stones
+#[cfg(not(feature = "bigint"))]
 type Number = u64;
+#[cfg(feature = "bigint")]
+type Number = num_bigint::BigUint;
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
 
-fn main() {
-    let test = read_input("125 17");
-    let input = read_input("28591 78 0 3159881 4254 524155 598 1");
+    type Input = Vec<Number>;
 
-    println!("First part test answer: {} == 55312", evaluate(&test, 25));
-    let before = Instant::now();
-    println!("First part answer: {}", evaluate(&input, 25));
-    println!("Work time: {:.2?}", before.elapsed());
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_input(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        evaluate(input, 25).into()
+    }
 
-    let before = Instant::now();
-    println!("Second part answer: {}", evaluate(&input, 75));
-    println!("Work time: {:.2?}", before.elapsed());
+    fn part_2(input: &Self::Input) -> Answer {
+        evaluate(input, 75).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let stones: Vec<u64> = (0..generator.size())
+            .map(|_| generator.number(0..1_000_000))
+            .collect();
+        Generated::new(stones.iter().join(" ") + "\n")
+    }
 }
 
-fn evaluate(input: &Vec<Number>, iterations: usize) -> usize {
+fn evaluate(input: &[Number], iterations: usize) -> Number {
+    let stones = input.iter().cloned().zip(repeat(Number::one()));
     (0..iterations)
-        .into_iter()
-        .fold(
-            input
-                .iter()
-                .copied()
-                .fold(HashMap::new(), |mut map, value| {
-                    map.entry(value).and_modify(|c| *c += 1).or_insert(1);
-                    map
-                }),
-            |stones, _| {
+        .fold(tally(stones), |stones, _| {
+            tally(
                 stones
-                    .iter()
-                    .fold(HashMap::new(), |mut map, (number, count)| {
-                        for i in blink(*number) {
-                            map.entry(i).and_modify(|c| *c += count).or_insert(*count);
-                        }
-                        map
-                    })
-            },
-        )
+                    .into_iter()
+                    .flat_map(|(number, count)| blink(&number).into_iter().zip(repeat(count))),
+            )
+        })
         .values()
-        .sum()
+        .fold(Number::zero(), |total, count| arith::add(&total, count))
 }
 
-fn blink(x: Number) -> Vec<Number> {
-    match x {
-        0 => vec![1],
-        _ => {
-            let n = digit_count(x);
-            if n % 2 == 0 {
-                let (a, b) = split_number(x);
-                vec![a, b]
-            } else {
-                vec![x * 2024]
+/// Adds up the counts of the stones with the same number
+fn tally(stones: impl Iterator<Item = (Number, Number)>) -> HashMap<Number, Number> {
+    let mut tally: HashMap<Number, Number> = HashMap::new();
+    for (number, count) in stones {
+        match tally.get_mut(&number) {
+            Some(total) => *total = arith::add(total, &count),
+            None => {
+                tally.insert(number, count);
             }
         }
     }
+    tally
 }
 
-fn split_number(x: Number) -> (Number, Number) {
-    let n = Number::pow(10, (digit_count(x) / 2) as u32);
-    (x / n, x % n)
+fn blink(x: &Number) -> Vec<Number> {
+    if x.is_zero() {
+        return vec![Number::one()];
+    }
+    let n = x.digit_count();
+    if n.is_multiple_of(2) {
+        let (a, b) = x.split_digits(n / 2);
+        vec![a, b]
+    } else {
+        vec![arith::mul(x, &Number::from(2024_u16))]
+    }
 }
 
-fn digit_count(number: Number) -> usize {
-    f64::log10(number as f64) as usize + 1
+fn read_input(input: &str) -> Result<Vec<Number>> {
+    Source::new(input).numbers(input, &[' ', '\r', '\n'])
 }
 
-fn read_input(input: &str) -> Vec<Number> {
-    input
-        .trim()
-        .split(" ")
-        .map(|s| s.parse::<Number>().unwrap())
-        .collect()
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::utilities::read_example;
+
+    /// Blinks at every stone of the line, one at a time
+    fn brute_force(input: &[Number], iterations: usize) -> usize {
+        let mut stones = input.to_vec();
+        for _ in 0..iterations {
+            stones = stones
+                .into_iter()
+                .flat_map(|stone| {
+                    let digits = stone.to_string();
+                    match digits.as_str() {
+                        "0" => vec![Number::one()],
+                        _ if digits.len() % 2 == 0 => {
+                            let (left, right) = digits.split_at(digits.len() / 2);
+                            vec![left.parse().unwrap(), right.parse().unwrap()]
+                        }
+                        _ => vec![arith::mul(&stone, &Number::from(2024_u16))],
+                    }
+                })
+                .collect();
+        }
+        stones.len()
+    }
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            Day11::part_1(&Day11::parse(&read_example(11, "test")).unwrap()),
+            55312.into()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn counts_like_brute_force(
+            stones in prop::collection::vec(
+                prop_oneof![0..10_u32, 0..1_000_000_u32].prop_map(Number::from),
+                1..5
+            ),
+            iterations in 0..15_usize,
+        ) {
+            prop_assert_eq!(
+                Answer::from(evaluate(&stones, iterations)),
+                Answer::from(brute_force(&stones, iterations))
+            );
+        }
+    }
 }
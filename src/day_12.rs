@@ -1,15 +1,19 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fs::read_to_string,
-    time::Instant,
-    usize,
-};
+use std::collections::{HashSet, VecDeque};
+
+use itertools::Itertools;
 
-type Position = (usize, usize);
+use crate::{
+    generate::{Generated, Generator},
+    grid::Grid,
+    parse::Source,
+    point::{Direction, Point},
+    render::{Canvas, Color},
+    Answer, Part, Result, Solution,
+};
 
-struct GardenRegion {
+pub struct GardenRegion {
     label: char,
-    positions: Vec<Position>,
+    positions: Vec<Point>,
 }
 
 impl GardenRegion {
@@ -20,19 +24,21 @@ impl GardenRegion {
     fn perimeter(&self) -> usize {
         self.positions
             .iter()
-            .map(|p| plus_neighbors(p))
-            .flatten()
-            .filter(|&(i, j)| !self.positions.contains(&(i as usize, j as usize)))
+            .flat_map(|&point| Direction::ORTHOGONAL.map(|direction| point.step(direction)))
+            .filter(|neighbor| !self.positions.contains(neighbor))
             .count()
     }
 
     fn corners(&self) -> usize {
         let bmap = self.to_padded_bool_map();
-        (0..(bmap.len() - 1))
-            .flat_map(|j| (0..(bmap[0].len() - 1)).map(move |i| (i, j)))
-            .map(|(i, j)| {
-                let (a, b) = (bmap[j][i] as u8, bmap[j][i + 1] as u8);
-                let (c, d) = (bmap[j + 1][i] as u8, bmap[j + 1][i + 1] as u8);
+        bmap.positions()
+            .filter_map(|point| {
+                // Each 2x2 window has a corner where the region has one or
+                // three of its tiles, or two facing diagonally
+                let cell =
+                    |x, y| bmap.get(point + Point::new(x, y)).copied().unwrap_or(false) as u8;
+                let (a, b) = (cell(0, 0), cell(1, 0));
+                let (c, d) = (cell(0, 1), cell(1, 1));
 
                 match a + b + c + d {
                     1 => Some(1),
@@ -47,117 +53,141 @@ impl GardenRegion {
                     _ => None,
                 }
             })
-            .flatten()
             .sum()
     }
 
-    fn to_padded_bool_map(&self) -> Vec<Vec<bool>> {
-        let ((xmin, ymin), (xmax, ymax)) = self.get_boundaries();
-        let mut map = vec![vec![false; (xmax - xmin) + 3]; (ymax - ymin) + 3];
-        for &(x, y) in &self.positions {
-            map[y - ymin + 1][x - xmin + 1] = true;
+    fn to_padded_bool_map(&self) -> Grid<bool> {
+        let (min, max) = self.get_boundaries();
+        let mut map = Grid::new(
+            (max.x - min.x) as usize + 3,
+            (max.y - min.y) as usize + 3,
+            false,
+        );
+        for &point in &self.positions {
+            map[point - min + Point::new(1, 1)] = true;
         }
         map
     }
 
-    fn get_boundaries(&self) -> ((usize, usize), (usize, usize)) {
-        let (mut xmin, mut ymin) = (usize::MAX, usize::MAX);
-        let (mut xmax, mut ymax) = (usize::MIN, usize::MIN);
-        for &(x, y) in &self.positions {
-            if x < xmin {
-                xmin = x;
-            }
-            if y < ymin {
-                ymin = y;
-            }
-            if x > xmax {
-                xmax = x;
-            }
-            if y > ymax {
-                ymax = y;
-            }
+    fn get_boundaries(&self) -> (Point, Point) {
+        let (mut min, mut max) = (
+            Point::new(i32::MAX, i32::MAX),
+            Point::new(i32::MIN, i32::MIN),
+        );
+        for &point in &self.positions {
+            min = Point::new(min.x.min(point.x), min.y.min(point.y));
+            max = Point::new(max.x.max(point.x), max.y.max(point.y));
         }
-        ((xmin, ymin), (xmax, ymax))
+        (min, max)
     }
 }
 
-fn main() {
-    let before = Instant::now();
-    let example_1 = read_input("./input/day_12.test.ex1.txt");
-    let example_2 = read_input("./input/day_12.test.ex2.txt");
-    let test = read_input("./input/day_12.test.txt");
-    let input = read_input("./input/day_12.txt");
-    println!("Load time, all: {:.2?}\n", before.elapsed());
-
-    println!(
-        "First part example #1 answer: {} == 140",
-        part_1(&example_1)
-    );
-    println!(
-        "First part example #2 answer: {} == 772",
-        part_1(&example_2)
-    );
-    println!("First part test answer: {} == 1930", part_1(&test));
-    let before = Instant::now();
-    println!("First part answer: {}", part_1(&input));
-    println!("Work time: {:.2?}\n", before.elapsed());
-
-    println!(
-        "Second part example #1 answer: {} == 80",
-        part_2(&example_1)
-    );
-    println!("Second part test answer: {} == 1206", part_2(&test));
-    let before = Instant::now();
-    println!("Second part answer: {}", part_2(&input));
-    println!("Work time: {:.2?}", before.elapsed());
-}
+pub struct Day12;
 
-fn part_1(gardens: &Vec<GardenRegion>) -> usize {
-    gardens.iter().map(|g| g.area() * g.perimeter()).sum()
-}
+impl Solution for Day12 {
+    const DAY: u8 = 12;
 
-fn part_2(gardens: &Vec<GardenRegion>) -> usize {
-    gardens.iter().map(|g| g.area() * g.corners()).sum()
-}
+    type Input = Vec<GardenRegion>;
 
-fn plus_neighbors(position: &Position) -> Vec<(i32, i32)> {
-    let (x, y) = (position.0 as i32, position.1 as i32);
-    vec![(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
-}
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(find_regions(&Source::new(input).grid(input)?))
+    }
 
-fn neighbors(input: &Vec<Vec<char>>, position: Position) -> Vec<Position> {
-    let (x, y) = position;
-    let label = input[y][x];
-    let mut result = Vec::new();
-    for (i, j) in plus_neighbors(&position) {
-        if let Some(row) = input.get(j as usize) {
-            if let Some(other_label) = row.get(i as usize) {
-                if *other_label == label {
-                    result.push((i as usize, j as usize));
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let side = generator.size() + 4;
+        let mut garden = Grid::new(side, side, 'A');
+        for point in garden.positions().collect_vec() {
+            garden[point] = *generator.pick(&['A', 'B', 'C', 'D', 'E']);
+        }
+        // Cells copying their neighbours grow the noise into regions
+        for _ in 0..3 {
+            for point in garden.positions().collect_vec() {
+                if generator.chance(0.7) {
+                    let around = garden.neighbors(point).map(|(_, &c)| c).collect_vec();
+                    garden[point] = *generator.pick(&around);
                 }
             }
         }
+        Generated::new(garden.to_string() + "\n")
+    }
+
+    fn render(input: &Self::Input, _: Part) -> Option<Canvas> {
+        let owners = region_map(input);
+        let plot = owners.map(|&owner| input[owner].label);
+
+        // Each region takes a colour that none of its neighbours took before it
+        let mut colors: Vec<Color> = Vec::with_capacity(input.len());
+        for (index, region) in input.iter().enumerate() {
+            let taken: Vec<Color> = region
+                .positions
+                .iter()
+                .flat_map(|&point| owners.neighbors(point))
+                .filter(|&(_, &owner)| owner < index)
+                .map(|(_, &owner)| colors[owner])
+                .collect();
+            let color = Color::PALETTE
+                .into_iter()
+                .find(|color| !taken.contains(color))
+                .unwrap_or(Color::PALETTE[index % Color::PALETTE.len()]);
+            colors.push(color);
+        }
+
+        let canvas = input
+            .iter()
+            .zip(colors)
+            .fold(Canvas::new(plot), |canvas, (region, color)| {
+                canvas.highlight("region", color, region.positions.iter().copied())
+            });
+        Some(canvas)
     }
-    result
 }
 
-fn read_input(file_path: &str) -> Vec<GardenRegion> {
-    let plot: Vec<Vec<char>> = read_to_string(file_path)
-        .unwrap()
-        .trim()
-        .split("\n")
-        .map(|row| row.trim().chars().map(|c| c).collect())
-        .collect();
+/// The index of the region each plot belongs to
+fn region_map(gardens: &[GardenRegion]) -> Grid<usize> {
+    let corner = gardens
+        .iter()
+        .flat_map(|region| region.positions.iter())
+        .fold(Point::ORIGIN, |max, point| {
+            Point::new(max.x.max(point.x), max.y.max(point.y))
+        });
+    let mut owners = Grid::new(corner.x as usize + 1, corner.y as usize + 1, 0);
+    for (index, region) in gardens.iter().enumerate() {
+        for &point in &region.positions {
+            owners[point] = index;
+        }
+    }
+    owners
+}
+
+fn part_1(gardens: &[GardenRegion]) -> usize {
+    gardens.iter().map(|g| g.area() * g.perimeter()).sum()
+}
+
+fn part_2(gardens: &[GardenRegion]) -> usize {
+    gardens.iter().map(|g| g.area() * g.corners()).sum()
+}
 
+fn neighbors(plot: &Grid<char>, point: Point) -> Vec<Point> {
+    let label = plot[point];
+    plot.neighbors(point)
+        .filter(|(_, &other_label)| other_label == label)
+        .map(|(other, _)| other)
+        .collect()
+}
+
+fn find_regions(plot: &Grid<char>) -> Vec<GardenRegion> {
     let mut areas: Vec<GardenRegion> = Vec::new();
-    let mut visited: HashSet<Position> = HashSet::new();
-    let mut queue: VecDeque<Position> = VecDeque::new();
-    for position in plot
-        .iter()
-        .enumerate()
-        .map(|(y, row)| row.iter().enumerate().map(move |(x, _)| (x, y)))
-        .flatten()
-    {
+    let mut visited: HashSet<Point> = HashSet::new();
+    let mut queue: VecDeque<Point> = VecDeque::new();
+    for position in plot.positions() {
         if visited.contains(&position) {
             continue;
         }
@@ -166,14 +196,14 @@ fn read_input(file_path: &str) -> Vec<GardenRegion> {
         queue.push_back(position);
 
         let mut new_area = GardenRegion {
-            label: plot[position.1][position.0],
+            label: plot[position],
             positions: Vec::new(),
         };
         while let Some(next) = queue.pop_front() {
             if visited.contains(&next) {
                 continue;
             }
-            for neighbor in neighbors(&plot, next) {
+            for neighbor in neighbors(plot, next) {
                 queue.push_back(neighbor);
             }
             new_area.positions.push(next);
@@ -184,3 +214,68 @@ fn read_input(file_path: &str) -> Vec<GardenRegion> {
 
     areas
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::utilities::read_example;
+
+    /// Counts the sides of a region by following its fences: each fence
+    /// starts a new side unless the tile beside it has the same fence
+    fn brute_force_sides(region: &GardenRegion) -> usize {
+        let tiles: HashSet<Point> = region.positions.iter().copied().collect();
+        let fenced = |tile: Point, direction: Direction| {
+            tiles.contains(&tile) && !tiles.contains(&tile.step(direction))
+        };
+        tiles
+            .iter()
+            .flat_map(|&tile| Direction::ORTHOGONAL.map(|direction| (tile, direction)))
+            .filter(|&(tile, direction)| {
+                fenced(tile, direction) && !fenced(tile.step(direction.turn_left()), direction)
+            })
+            .count()
+    }
+
+    #[test]
+    fn part_1_examples() {
+        assert_eq!(
+            part_1(&Day12::parse(&read_example(12, "test.ex1")).unwrap()),
+            140
+        );
+        assert_eq!(
+            part_1(&Day12::parse(&read_example(12, "test.ex2")).unwrap()),
+            772
+        );
+        assert_eq!(
+            part_1(&Day12::parse(&read_example(12, "test")).unwrap()),
+            1930
+        );
+    }
+
+    #[test]
+    fn part_2_examples() {
+        assert_eq!(
+            part_2(&Day12::parse(&read_example(12, "test.ex1")).unwrap()),
+            80
+        );
+        assert_eq!(
+            part_2(&Day12::parse(&read_example(12, "test")).unwrap()),
+            1206
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn corners_count_sides(
+            width in 1..7_usize,
+            cells in prop::collection::vec(prop::sample::select(vec!['A', 'B', 'C']), 36),
+        ) {
+            let plot = Grid::from_cells(width, cells[..width * (36 / width)].to_vec());
+            for region in find_regions(&plot) {
+                prop_assert_eq!(region.corners(), brute_force_sides(&region));
+            }
+        }
+    }
+}
@@ -1,36 +1,50 @@
-use std::{fmt::Debug, fs::read_to_string};
+use std::fmt::Debug;
 
-use nalgebra::*;
-use regex::Regex;
+use num_traits::Zero;
 
-struct ClawMachine {
-    offsets_a: (u64, u64),
-    offsets_b: (u64, u64),
-    reward_location: (u64, u64),
+use crate::{
+    arith,
+    generate::{Generated, Generator},
+    params::{Param, Params},
+    parse::Source,
+    Answer, Result, Solution,
+};
+
+#[cfg(not(feature = "bigint"))]
+type Number = i64;
+#[cfg(feature = "bigint")]
+type Number = num_bigint::BigInt;
+
+pub struct ClawMachine {
+    offsets_a: (Number, Number),
+    offsets_b: (Number, Number),
+    reward_location: (Number, Number),
 }
 
 impl ClawMachine {
-    fn solve(&self) -> Option<(u64, u64)> {
-        let a = Matrix2::new(
-            self.offsets_a.0 as f64,
-            self.offsets_b.0 as f64,
-            self.offsets_a.1 as f64,
-            self.offsets_b.1 as f64,
+    /// Presses of each button that reach the prize, moved by `prize_offset`
+    /// along both axes. Found exactly with Cramer's rule, so buttons moving
+    /// the same way, which leave the presses undetermined, never win.
+    fn solve(&self, prize_offset: &Number) -> Option<(Number, Number)> {
+        let ((ax, ay), (bx, by)) = (&self.offsets_a, &self.offsets_b);
+        let (px, py) = (
+            &arith::add(&self.reward_location.0, prize_offset),
+            &arith::add(&self.reward_location.1, prize_offset),
         );
-        let b = Vector2::new(self.reward_location.0 as f64, self.reward_location.1 as f64);
-        match a.lu().solve(&b) {
-            Some(solution) => {
-                let error = f64::max(
-                    ((solution[0].round()) as f64 - solution[0]).abs(),
-                    ((solution[1].round()) as f64 - solution[1]).abs(),
-                );
-                return if error < 0.001 {
-                    Some((solution[0].round() as u64, solution[1].round() as u64))
-                } else {
-                    None
-                };
-            }
-            None => None,
+        let determinant = &arith::sub(&arith::mul(ax, by), &arith::mul(ay, bx));
+        if determinant.is_zero() {
+            return None;
+        }
+        let a = &arith::sub(&arith::mul(px, by), &arith::mul(py, bx));
+        let b = &arith::sub(&arith::mul(ax, py), &arith::mul(ay, px));
+        if !(a % determinant).is_zero() || !(b % determinant).is_zero() {
+            return None;
+        }
+        // Buttons cannot be pressed a negative number of times
+        let (a, b) = (a / determinant, b / determinant);
+        match a < Number::zero() || b < Number::zero() {
+            true => None,
+            false => Some((a, b)),
         }
     }
 }
@@ -45,63 +59,170 @@ impl Debug for ClawMachine {
     }
 }
 
-fn main() {
-    let test = read_input("./input/day_13.test.txt");
-    let input = read_input("./input/day_13.txt");
-    println!("First part test answer: {} == 480", part_1(&test));
-    println!("First part answer: {}", part_1(&input));
-    println!("Second part answer: {}", part_2(&input));
+/// The claw machines, with how far the prizes really are in the second part
+pub struct Arcade {
+    machines: Vec<ClawMachine>,
+    prize_offset: Number,
 }
 
-fn part_1(input: &Vec<ClawMachine>) -> u64 {
-    input
-        .iter()
-        .filter_map(|f| f.solve())
-        .map(|f| {
-            let (a, b) = f;
-            a * 3 + b
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = Arcade;
+
+    const PARAMS: &'static [Param] = &[Param::new(
+        "prize_offset",
+        "10000000000000",
+        "added to both coordinates of every prize in the second part",
+    )];
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        Ok(Arcade {
+            machines: read_input(input)?,
+            prize_offset: params.get("prize_offset")?,
         })
-        .sum()
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(&input.machines).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(&input.machines, &input.prize_offset).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let mut machines = Vec::new();
+        while machines.len() < generator.size() {
+            let a: (u64, u64) = (generator.number(10..100), generator.number(10..100));
+            let b: (u64, u64) = (generator.number(10..100), generator.number(10..100));
+            // Buttons moving the same way leave the presses undetermined
+            if a.0 * b.1 == a.1 * b.0 {
+                continue;
+            }
+            let prize = match generator.chance(0.5) {
+                true => {
+                    let (presses_a, presses_b): (u64, u64) =
+                        (generator.number(1..=100), generator.number(1..=100));
+                    (
+                        presses_a * a.0 + presses_b * b.0,
+                        presses_a * a.1 + presses_b * b.1,
+                    )
+                }
+                false => (generator.number(1000..20000), generator.number(1000..20000)),
+            };
+            machines.push(format!(
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+                a.0, a.1, b.0, b.1, prize.0, prize.1
+            ));
+        }
+        Generated::new(machines.join("\n"))
+    }
+}
+
+fn part_1(input: &[ClawMachine]) -> Number {
+    tokens(input, &Number::zero())
+}
+
+fn part_2(input: &[ClawMachine], prize_offset: &Number) -> Number {
+    tokens(input, prize_offset)
 }
 
-fn part_2(input: &Vec<ClawMachine>) -> u64 {
+/// Tokens it takes to win every prize that can be won, at three per press of
+/// button A and one per press of button B
+fn tokens(input: &[ClawMachine], prize_offset: &Number) -> Number {
+    let price = Number::from(3);
     input
         .iter()
-        .map(|f| ClawMachine {
-            offsets_a: f.offsets_a,
-            offsets_b: f.offsets_b,
-            reward_location: (
-                10000000000000u64 + f.reward_location.0,
-                10000000000000u64 + f.reward_location.1,
-            ),
+        .filter_map(|machine| machine.solve(prize_offset))
+        .fold(Number::zero(), |total, (a, b)| {
+            arith::add(&total, &arith::add(&arith::mul(&a, &price), &b))
         })
-        .filter_map(|f| f.solve())
-        .map(|f| {
-            let (a, b) = f;
-            a * 3 + b
-        })
-        .sum()
 }
 
-fn read_input(file_path: &str) -> Vec<ClawMachine> {
-    let pattern = Regex::new(
-        r"Button A: X\+(\d+), Y\+(\d+)\nButton B: X\+(\d+), Y\+(\d+)\nPrize: X=(\d+), Y=(\d+)",
-    )
-    .unwrap();
-    pattern
-        .captures_iter(read_to_string(file_path).unwrap().trim())
-        .map(|c| {
-            let ds: Vec<u64> = c
-                .extract::<6>()
-                .1
-                .iter()
-                .map(|d| d.parse::<u64>().unwrap())
-                .collect();
-            ClawMachine {
-                offsets_a: (ds[0], ds[1]),
-                offsets_b: (ds[2], ds[3]),
-                reward_location: (ds[4], ds[5]),
-            }
+fn read_input(input: &str) -> Result<Vec<ClawMachine>> {
+    Source::new(input)
+        .sections()
+        .map(|machine| {
+            let mut lines = machine.lines();
+            let mut line = |template| {
+                let line = lines.next().ok_or_else(|| machine.missing(template))?;
+                machine.fields::<Number, 2>(template, line)
+            };
+            let [a_x, a_y] = line("Button A: X+{}, Y+{}")?;
+            let [b_x, b_y] = line("Button B: X+{}, Y+{}")?;
+            let [prize_x, prize_y] = line("Prize: X={}, Y={}")?;
+            Ok(ClawMachine {
+                offsets_a: (a_x, a_y),
+                offsets_b: (b_x, b_y),
+                reward_location: (prize_x, prize_y),
+            })
         })
         .collect()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::utilities::read_example;
+
+    /// Tries every number of presses of button A that does not overshoot
+    fn brute_force(
+        (ax, ay): (u32, u32),
+        (bx, by): (u32, u32),
+        (px, py): (u32, u32),
+    ) -> Option<(u32, u32)> {
+        (0..=px / ax).find_map(|a| {
+            let (rest_x, rest_y) = (px - a * ax, py.checked_sub(a * ay)?);
+            let b = rest_x / bx;
+            (rest_x % bx == 0 && b * by == rest_y).then_some((a, b))
+        })
+    }
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            Day13::part_1(&Day13::parse(&read_example(13, "test")).unwrap()),
+            480.into()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn solves_like_brute_force(
+            offsets_a in (1..30_u32, 1..30_u32),
+            offsets_b in (1..30_u32, 1..30_u32),
+            presses in (0..50_u32, 0..50_u32),
+            prize in (0..2000_u32, 0..2000_u32),
+            winnable: bool,
+        ) {
+            // Collinear buttons leave the presses undetermined
+            prop_assume!(offsets_a.0 * offsets_b.1 != offsets_a.1 * offsets_b.0);
+            let reward_location = match winnable {
+                true => (
+                    presses.0 * offsets_a.0 + presses.1 * offsets_b.0,
+                    presses.0 * offsets_a.1 + presses.1 * offsets_b.1,
+                ),
+                false => prize,
+            };
+            let pair = |(x, y): (u32, u32)| (Number::from(x), Number::from(y));
+            let machine = ClawMachine {
+                offsets_a: pair(offsets_a),
+                offsets_b: pair(offsets_b),
+                reward_location: pair(reward_location),
+            };
+            prop_assert_eq!(
+                machine.solve(&Number::zero()),
+                brute_force(offsets_a, offsets_b, reward_location).map(pair)
+            );
+        }
+    }
+}
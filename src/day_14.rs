@@ -1,60 +1,173 @@
-use std::{fs::read_to_string, time::Instant};
-
 use itertools::Itertools;
 use rayon::prelude::*;
-use regex::Regex;
 
-struct Robot {
-    start: (i32, i32),
-    velocity: (i32, i32),
+use crate::{
+    animation::Recorder,
+    generate::{Generated, Generator},
+    grid::Grid,
+    params::{Param, Params},
+    parse::Source,
+    point::Point,
+    render::{Canvas, Color},
+    Answer, Part, Result, Solution,
+};
+
+pub struct Robot {
+    start: Point,
+    velocity: Point,
+}
+
+/// The robots, with the size of the area they patrol
+pub struct Bathroom {
+    robots: Vec<Robot>,
+    area: (i32, i32),
+    seconds: usize,
 }
 
-fn main() {
-    let test_area = (11, 7);
-    let real_area = (101, 103);
-    let test = read_input("./input/day_14.test.txt");
-    let input = read_input("./input/day_14.txt");
+pub struct Day14;
 
-    println!(
-        "First part test answer: {}",
-        evaluate(&test, test_area, 100)
-    );
-    println!("First part answer: {}", evaluate(&input, real_area, 100));
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = Bathroom;
+
+    const PARAMS: &'static [Param] = &[
+        Param::new("width", "101", "width of the area"),
+        Param::new("height", "103", "height of the area"),
+        Param::new(
+            "seconds",
+            "100",
+            "seconds the robots move in the first part",
+        ),
+    ];
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        let robots = read_input(input)?;
+        // Every robot starts inside the area
+        let (width, height) = robots.iter().fold((1, 1), |(width, height), robot| {
+            (width.max(robot.start.x + 1), height.max(robot.start.y + 1))
+        });
+        Ok(Bathroom {
+            area: (
+                params.get_within("width", width..)?,
+                params.get_within("height", height..)?,
+            ),
+            seconds: params.get("seconds")?,
+            robots,
+        })
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        evaluate(&input.robots, input.area, input.seconds).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        find_image(&input.robots, input.area).into()
+    }
 
-    let before = Instant::now();
-    println!("Second part answer: {}", find_image(&input, real_area));
-    println!("Work time: {:.2?}", before.elapsed());
+    fn generate(generator: &mut Generator) -> Generated {
+        let (width, height) = (
+            2 * generator.size() as i32 + 1,
+            2 * generator.size() as i32 + 3,
+        );
+        // The robots all stand apart at some second, where the second part
+        // finds its picture
+        let seconds: i32 = generator.number(1..=200);
+        let mut spots = (0..width)
+            .cartesian_product(0..height)
+            .map(|(x, y)| Point::new(x, y))
+            .collect_vec();
+        generator.shuffle(&mut spots);
+        let mut input = String::new();
+        for &spot in &spots[..2 * generator.size()] {
+            let velocity = Point::new(generator.number(-5..=5), generator.number(-5..=5));
+            let start = spot - velocity * seconds;
+            let start = Point::new(start.x.rem_euclid(width), start.y.rem_euclid(height));
+            input.push_str(&format!(
+                "p={},{} v={},{}\n",
+                start.x, start.y, velocity.x, velocity.y
+            ));
+        }
+        Generated::new(input)
+            .with("width", width)
+            .with("height", height)
+    }
+
+    fn render(input: &Self::Input, part: Part) -> Option<Canvas> {
+        let seconds = match part {
+            Part::One => input.seconds,
+            Part::Two => find_image(&input.robots, input.area)?,
+        };
+        Some(floor(input, seconds))
+    }
+
+    fn animate(input: &Self::Input, part: Part, recorder: &mut Recorder) -> bool {
+        let seconds = match part {
+            Part::One => input.seconds,
+            Part::Two => match find_image(&input.robots, input.area) {
+                Some(seconds) => seconds,
+                None => return false,
+            },
+        };
+        for second in 0..=seconds {
+            recorder.frame(|| floor(input, second));
+            if recorder.is_done() {
+                break;
+            }
+        }
+        true
+    }
 }
 
-fn find_image(input: &Vec<Robot>, boundaries: (i32, i32)) -> usize {
-    *(0..10000)
-        .par_bridge()
-        .filter(|&i| simulate(input, boundaries, i).iter().unique().count() == input.len())
-        .collect::<Vec<_>>()
-        .first()
-        .unwrap()
+/// The robots after `seconds`, drawn on the floor they patrol
+fn floor(input: &Bathroom, seconds: usize) -> Canvas {
+    let (width, height) = input.area;
+    let floor = Grid::new(width as usize, height as usize, '.');
+    let robots = simulate(&input.robots, input.area, seconds);
+    Canvas::new(floor).mark(
+        &format!("robots after {seconds} seconds"),
+        Color::Green,
+        '#',
+        robots,
+    )
 }
 
-fn simulate(input: &Vec<Robot>, boundaries: (i32, i32), ticks: usize) -> Vec<(i32, i32)> {
+/// The first second the robots all stand apart, if it comes soon enough
+fn find_image(input: &[Robot], boundaries: (i32, i32)) -> Option<usize> {
+    (0..10000)
+        .into_par_iter()
+        .find_first(|&i| simulate(input, boundaries, i).iter().unique().count() == input.len())
+}
+
+fn simulate(input: &[Robot], boundaries: (i32, i32), ticks: usize) -> Vec<Point> {
     let (width, height) = boundaries;
+    // Robots are back where they started along an axis after as many seconds
+    // as it is long, which keeps any number of seconds from overflowing
+    let walk = |start: i32, velocity: i32, length: i32| {
+        let ticks = (ticks % length as usize) as i64;
+        (start as i64 + velocity as i64 * ticks).rem_euclid(length as i64) as i32
+    };
     input
         .iter()
         .map(|robot| {
-            let t = (
-                robot.start.0 + (robot.velocity.0 * ticks as i32) % width,
-                robot.start.1 + (robot.velocity.1 * ticks as i32) % height,
-            );
-            ((t.0 + width) % width, (t.1 + height) % height)
+            Point::new(
+                walk(robot.start.x, robot.velocity.x, width),
+                walk(robot.start.y, robot.velocity.y, height),
+            )
         })
         .collect()
 }
 
-fn evaluate(input: &Vec<Robot>, boundaries: (i32, i32), ticks: usize) -> usize {
+fn evaluate(input: &[Robot], boundaries: (i32, i32), ticks: usize) -> usize {
     let (width, height) = boundaries;
     let (mid_w, mid_h) = ((width - 1) / 2, (height - 1) / 2);
     let quadrants = simulate(input, boundaries, ticks).iter().fold(
         (0, 0, 0, 0),
-        |(q1, q2, q3, q4), &(x, y)| {
+        |(q1, q2, q3, q4), &Point { x, y }| {
             if x < mid_w {
                 if y < mid_h {
                     return (q1 + 1, q2, q3, q4);
@@ -74,23 +187,59 @@ fn evaluate(input: &Vec<Robot>, boundaries: (i32, i32), ticks: usize) -> usize {
     quadrants.0 * quadrants.1 * quadrants.2 * quadrants.3
 }
 
-fn read_input(file_path: &str) -> Vec<Robot> {
-    let pattern = Regex::new(r"p=(\d+),(\d+) v=([\d\-]+),([\d\-]+)").unwrap();
-    read_to_string(file_path)
-        .unwrap()
-        .trim()
-        .split("\n")
-        .map(|f| {
-            pattern
-                .captures(f)
-                .unwrap()
-                .extract::<4>()
-                .1
-                .map(|f| f.parse::<i32>().unwrap())
-        })
-        .map(|c| Robot {
-            start: (c[0], c[1]),
-            velocity: (c[2], c[3]),
+fn read_input(input: &str) -> Result<Vec<Robot>> {
+    let source = Source::new(input);
+    source
+        .lines()
+        .map(|line| {
+            let [x, y, dx, dy] = source.fields("p={},{} v={},{}", line)?;
+            Ok(Robot {
+                start: Point::new(x, y),
+                velocity: Point::new(dx, dy),
+            })
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            evaluate(
+                &Day14::parse(&read_example(14, "test")).unwrap().robots,
+                (11, 7),
+                100
+            ),
+            12
+        );
+    }
+
+    #[test]
+    fn robots_start_inside_the_area() {
+        let mut params = Params::defaults(Day14::PARAMS);
+        params.set("width", "0");
+        let Err(refused) = Day14::parse_with(&read_example(14, "test"), &params) else {
+            panic!("an area without width was accepted");
+        };
+        assert_eq!(
+            refused.to_string(),
+            "bad parameter `width`: `0` is out of range, expected at least 11"
+        );
+    }
+
+    #[test]
+    fn many_seconds() {
+        let robots = Day14::parse(&read_example(14, "test")).unwrap().robots;
+        // A whole number of rounds of both axes later, all are back
+        let rounds = 11 * 7 * 1_000_000_000_000;
+        assert_eq!(
+            simulate(&robots, (11, 7), rounds + 100),
+            simulate(&robots, (11, 7), 100)
+        );
+        assert_eq!(evaluate(&robots, (11, 7), rounds + 100), 12);
+    }
+}
@@ -1,212 +1,279 @@
-use std::fs::read_to_string;
-
 use itertools::Itertools;
-use regex::Regex;
-
-enum Instruction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-
-fn main() {
-    let small_1 = read_input("./input/day_15.test.small_1.txt");
-    let small_2 = read_input("./input/day_15.test.small_2.txt");
-    let test = read_input("./input/day_15.test.txt");
-    let input = read_input("./input/day_15.txt");
-
-    println!(
-        "First part small test answer: {} == 2028",
-        part_1(&small_1.0, &small_1.1)
-    );
-    println!(
-        "First part test answer: {} == 10092",
-        part_1(&test.0, &test.1)
-    );
-    println!("First part answer: {}", part_1(&input.0, &input.1));
-
-    println!(
-        "Second part small test answer: {}",
-        part_2(&small_2.0, &small_2.1)
-    );
-    println!(
-        "Second part test answer: {} == 9021",
-        part_2(&test.0, &test.1)
-    );
-    println!("Second part answer: {}", part_2(&input.0, &input.1));
-}
-
-fn part_1(map: &Vec<Vec<char>>, instuctions: &Vec<Instruction>) -> usize {
-    run(map, instuctions)
-        .iter()
-        .enumerate()
-        .map(|(j, row)| {
-            row.iter()
-                .enumerate()
-                .filter_map(|(i, c)| match c {
-                    'O' => Some((100 * j + i) as usize),
-                    _ => None,
-                })
-                .sum::<usize>()
-        })
-        .sum()
+
+use crate::{
+    animation::Recorder,
+    generate::{Generated, Generator},
+    grid::Grid,
+    parse::Source,
+    point::{Direction, Point},
+    render::{Canvas, Color},
+    Answer, Part, Result, Solution,
+};
+
+/// The map of the warehouse, where the robot starts, and how it moves
+pub struct Warehouse {
+    map: Grid<char>,
+    robot: Point,
+    movements: Vec<Direction>,
 }
 
-fn part_2(map: &Vec<Vec<char>>, instuctions: &Vec<Instruction>) -> usize {
-    let modified: Vec<Vec<char>> = map
-        .iter()
-        .map(|row| {
-            row.iter()
-                .map(|c| match c {
-                    '#' => "##",
-                    '.' => "..",
-                    'O' => "[]",
-                    '@' => "@.",
-                    _ => "",
-                })
-                .join("")
-                .chars()
-                .collect()
-        })
-        .collect();
-
-    run(&modified, instuctions)
-        .iter()
-        .enumerate()
-        .map(|(j, row)| {
-            row.iter()
-                .enumerate()
-                .filter_map(|(i, c)| match c {
-                    '[' => Some((100 * j + i) as usize),
-                    _ => None,
-                })
-                .sum::<usize>()
-        })
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = Warehouse;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_input(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let side = generator.size() + 4;
+        let mut map = Grid::new(side, side, '#');
+        for point in map.positions().collect_vec() {
+            let inside =
+                (1..side as i32 - 1).contains(&point.x) && (1..side as i32 - 1).contains(&point.y);
+            if inside {
+                map[point] = match generator.number(0..10) {
+                    0 => '#',
+                    1 | 2 => 'O',
+                    _ => '.',
+                };
+            }
+        }
+        let free = map.find_all(|&c| c == '.').collect_vec();
+        map[*generator.pick(&free)] = '@';
+
+        let movements = generator.word("^v<>", 10 * side);
+        let lines = movements
+            .as_bytes()
+            .chunks(70)
+            .map(|line| String::from_utf8_lossy(line));
+        Generated::new(format!("{map}\n\n{}\n", lines.collect_vec().join("\n")))
+    }
+
+    fn render(input: &Self::Input, part: Part) -> Option<Canvas> {
+        let after = match part {
+            Part::One => run(&input.map, input.robot, &input.movements),
+            Part::Two => run(&widen(&input.map), wide(input.robot), &input.movements),
+        };
+        Some(warehouse(after))
+    }
+
+    fn animate(input: &Self::Input, part: Part, recorder: &mut Recorder) -> bool {
+        let (map, robot) = match part {
+            Part::One => (input.map.clone(), input.robot),
+            Part::Two => (widen(&input.map), wide(input.robot)),
+        };
+        recorder.frame(|| warehouse(map.clone()));
+        run_observed(&map, robot, &input.movements, |map| {
+            recorder.frame(|| warehouse(map.clone()));
+        });
+        true
+    }
+}
+
+/// The warehouse with its boxes and robot picked out
+fn warehouse(map: Grid<char>) -> Canvas {
+    let boxes: Vec<Point> = map.find_all(|&c| matches!(c, 'O' | '[' | ']')).collect();
+    let robot = map.find(|&c| c == '@');
+    Canvas::new(map)
+        .highlight("box", Color::Yellow, boxes)
+        .highlight("robot", Color::Red, robot)
+}
+
+/// Sum of the GPS coordinates of every box, found by its left `tile`
+fn box_coordinates(map: &Grid<char>, tile: char) -> usize {
+    map.find_all(|&c| c == tile)
+        .map(|point| 100 * point.y as usize + point.x as usize)
         .sum()
 }
 
-fn run(map: &Vec<Vec<char>>, instuctions: &Vec<Instruction>) -> Vec<Vec<char>> {
-    let start = map
-        .iter()
-        .enumerate()
-        .filter_map(|(j, row)| {
-            row.iter()
-                .enumerate()
-                .filter_map(move |(i, c)| match *c {
-                    '@' => Some((i, j)),
-                    _ => None,
-                })
-                .next()
-        })
-        .next()
-        .unwrap();
-    let mut current_pos = start;
-    let mut current_map = map.clone();
-    for instuction in instuctions {
-        let (x, y) = current_pos;
-        let (dx, dy) = match instuction {
-            Instruction::Left => (-1, 0),
-            Instruction::Right => (1, 0),
-            Instruction::Up => (0, -1),
-            Instruction::Down => (0, 1),
-        };
+fn part_1(input: &Warehouse) -> usize {
+    box_coordinates(&run(&input.map, input.robot, &input.movements), 'O')
+}
+
+fn part_2(input: &Warehouse) -> usize {
+    let map = widen(&input.map);
+    box_coordinates(&run(&map, wide(input.robot), &input.movements), '[')
+}
+
+/// The warehouse of the second part, where everything but the robot is
+/// twice as wide
+fn widen(map: &Grid<char>) -> Grid<char> {
+    Grid::from_cells(
+        map.width() * 2,
+        map.iter()
+            .flat_map(|(_, c)| match c {
+                '#' => ['#', '#'],
+                'O' => ['[', ']'],
+                '@' => ['@', '.'],
+                _ => ['.', '.'],
+            })
+            .collect(),
+    )
+}
+
+/// Where a tile of the warehouse starts once it is twice as wide
+fn wide(point: Point) -> Point {
+    Point::new(2 * point.x, point.y)
+}
+
+fn run(map: &Grid<char>, robot: Point, instuctions: &[Direction]) -> Grid<char> {
+    run_observed(map, robot, instuctions, |_| {})
+}
 
-        if dy != 0 {
-            if can_vertical_push(&current_map, current_pos, dy) {
-                push_vertical(&mut current_map, current_pos, dy);
-                current_pos = (x, (y as i32 + dy) as usize)
+/// Moves the robot through every instruction, showing the map to `observe`
+/// after each of them
+fn run_observed(
+    map: &Grid<char>,
+    robot: Point,
+    instuctions: &[Direction],
+    mut observe: impl FnMut(&Grid<char>),
+) -> Grid<char> {
+    let mut current_pos = robot;
+    let mut current_map = map.clone();
+    for &direction in instuctions {
+        if direction.is_vertical() {
+            if can_vertical_push(&current_map, current_pos, direction) {
+                push_vertical(&mut current_map, current_pos, direction);
+                current_pos = current_pos.step(direction)
             }
         } else {
-            if can_horizontial_push(&current_map, current_pos, dx) {
-                push_horizontial(&mut current_map, current_pos, dx);
-                current_pos = ((x as i32 + dx) as usize, y)
+            if can_horizontial_push(&current_map, current_pos, direction) {
+                push_horizontial(&mut current_map, current_pos, direction);
+                current_pos = current_pos.step(direction)
             }
         }
+        observe(&current_map);
     }
-    current_map.clone()
+    current_map
 }
 
-fn push_horizontial(map: &mut Vec<Vec<char>>, position: (usize, usize), direction: i32) {
-    let new_pos = ((position.0 as i32 + direction) as usize, position.1);
-    match map[new_pos.1][new_pos.0] {
+fn push_horizontial(map: &mut Grid<char>, position: Point, direction: Direction) {
+    let new_pos = position.step(direction);
+    match map[new_pos] {
         'O' => push_horizontial(map, new_pos, direction),
         '[' => push_horizontial(map, new_pos, direction),
         ']' => push_horizontial(map, new_pos, direction),
         _ => (),
     };
-    map[new_pos.1][new_pos.0] = map[position.1][position.0];
-    map[position.1][position.0] = '.';
+    map[new_pos] = map[position];
+    map[position] = '.';
 }
 
-fn push_vertical(map: &mut Vec<Vec<char>>, position: (usize, usize), direction: i32) {
-    let new_pos = (position.0, (position.1 as i32 + direction) as usize);
-    match map[new_pos.1][new_pos.0] {
+fn push_vertical(map: &mut Grid<char>, position: Point, direction: Direction) {
+    let new_pos = position.step(direction);
+    match map[new_pos] {
         'O' => push_vertical(map, new_pos, direction),
         '[' => {
             push_vertical(map, new_pos, direction);
-            push_vertical(map, (new_pos.0 + 1, new_pos.1), direction);
+            push_vertical(map, new_pos.step(Direction::East), direction);
         }
         ']' => {
             push_vertical(map, new_pos, direction);
-            push_vertical(map, (new_pos.0 - 1, new_pos.1), direction);
+            push_vertical(map, new_pos.step(Direction::West), direction);
         }
         _ => (),
     }
-    map[new_pos.1][new_pos.0] = map[position.1][position.0];
-    map[position.1][position.0] = '.';
+    map[new_pos] = map[position];
+    map[position] = '.';
 }
 
-fn can_vertical_push(map: &Vec<Vec<char>>, position: (usize, usize), direction: i32) -> bool {
-    let (x, y) = position;
-    let ny = (y as i32 + direction) as usize;
-    match map[ny][x] {
+fn can_vertical_push(map: &Grid<char>, position: Point, direction: Direction) -> bool {
+    let next = position.step(direction);
+    // The edge of the map stops the robot like a wall
+    match map.get(next).copied().unwrap_or('#') {
         '#' => false,
         '.' => true,
-        'O' => can_vertical_push(map, (x, ny), direction),
+        'O' => can_vertical_push(map, next, direction),
         '[' => {
-            can_vertical_push(map, (x, ny), direction)
-                && can_vertical_push(map, (x + 1, ny), direction)
+            can_vertical_push(map, next, direction)
+                && can_vertical_push(map, next.step(Direction::East), direction)
         }
         ']' => {
-            can_vertical_push(map, (x - 1, ny), direction)
-                && can_vertical_push(map, (x, ny), direction)
+            can_vertical_push(map, next.step(Direction::West), direction)
+                && can_vertical_push(map, next, direction)
         }
-        c => panic!("Unknown tile: {c} {}", c as i32),
+        _ => unreachable!("tiles are checked when parsing"),
     }
 }
 
-fn can_horizontial_push(map: &Vec<Vec<char>>, position: (usize, usize), direction: i32) -> bool {
-    let (x, y) = position;
-    let nx = (x as i32 + direction) as usize;
-    match map[y][nx] {
+fn can_horizontial_push(map: &Grid<char>, position: Point, direction: Direction) -> bool {
+    let next = position.step(direction);
+    match map.get(next).copied().unwrap_or('#') {
         '#' => false,
         '.' => true,
-        _ => can_horizontial_push(map, (nx, y), direction),
+        _ => can_horizontial_push(map, next, direction),
     }
 }
 
-fn read_input(file_path: &str) -> (Vec<Vec<char>>, Vec<Instruction>) {
-    let break_pattern = Regex::new(r"(\r?\n){2,}").unwrap();
-    let string = read_to_string(file_path).unwrap();
-    let parts: Vec<&str> = break_pattern.split(&string).collect();
-
-    (
-        String::from(parts[0])
-            .split("\n")
-            .map(|s| s.chars().collect())
-            .collect(),
-        String::from(parts[1])
-            .split("\n")
-            .join("")
-            .chars()
-            .filter_map(|c| match c {
-                '^' => Some(Instruction::Up),
-                'v' => Some(Instruction::Down),
-                '<' => Some(Instruction::Left),
-                '>' => Some(Instruction::Right),
-                _ => None,
+fn read_input(input: &str) -> Result<Warehouse> {
+    let [map, movements] =
+        Source::new(input).split_sections(["warehouse map", "robot movements"])?;
+    let text = movements.text();
+    let movements = text
+        .char_indices()
+        .filter(|(_, c)| !c.is_whitespace())
+        .map(|(offset, c)| {
+            Direction::from_glyph(c).ok_or_else(|| {
+                movements.unexpected(
+                    &text[offset..offset + c.len_utf8()],
+                    "a movement `^`, `v`, `<` or `>`",
+                )
             })
-            .collect(),
-    )
+        })
+        .collect::<Result<_>>()?;
+    Ok(Warehouse {
+        map: map.grid_with(map.text(), "a tile `#`, `.`, `O` or `@`", |c| {
+            matches!(c, '#' | '.' | 'O' | '@').then_some(c)
+        })?,
+        robot: map.tile(map.text(), '@', "robot")?,
+        movements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_examples() {
+        let warehouse = Day15::parse(&read_example(15, "test.small_1")).unwrap();
+        assert_eq!(part_1(&warehouse), 2028);
+        let warehouse = Day15::parse(&read_example(15, "test")).unwrap();
+        assert_eq!(part_1(&warehouse), 10092);
+    }
+
+    #[test]
+    fn unknown_tiles() {
+        let Err(error) = Day15::parse("#####\n#@[]#\n#####\n\n<\n") else {
+            panic!("boxes of the first part are one tile wide");
+        };
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3 (in warehouse map): unexpected `[`, expected a tile `#`, `.`, `O` or `@`"
+        );
+        // Without walls, the edge of the map stops the robot
+        let warehouse = Day15::parse("@O.\n\n>>>^<<<<\n").unwrap();
+        assert_eq!(part_1(&warehouse), 2);
+    }
+
+    #[test]
+    fn part_2_examples() {
+        let warehouse = Day15::parse(&read_example(15, "test.small_2")).unwrap();
+        assert_eq!(part_2(&warehouse), 618);
+        let warehouse = Day15::parse(&read_example(15, "test")).unwrap();
+        assert_eq!(part_2(&warehouse), 9021);
+    }
 }
@@ -1,146 +1,188 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    fs::read_to_string,
-    usize,
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    generate::{Generated, Generator},
+    grid::Grid,
+    parse::Source,
+    point::{Direction, Point},
+    render::{Canvas, Color},
+    search::{self, Explored},
+    Answer, Part, Result, Solution,
 };
 
-use itertools::Itertools;
+/// Tile of the reindeer and the way it faces
+type Reindeer = (Point, Direction);
 
-pub struct Path {
-    pub visited: Vec<(usize, usize)>,
-    last_direction: (i32, i32),
-    turns: usize,
+/// The cheapest ways through the maze, and the states they finish in
+struct Paths {
+    explored: Explored<Reindeer, usize>,
+    ends: Vec<Reindeer>,
 }
 
-impl Path {
-    fn score(&self) -> usize {
-        self.visited.len() - 1 + self.turns * 1000
-    }
+/// The map of the maze, with its start and end tiles
+pub struct Maze {
+    map: Grid<char>,
+    start: Point,
+    end: Point,
 }
 
-fn main() {
-    let test_1 = read_input("./input/day_16.test.1.txt");
-    let test_2 = read_input("./input/day_16.test.2.txt");
-    let input = read_input("./input/day_16.txt");
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+
+    type Input = Maze;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let source = Source::new(input);
+        Ok(Maze {
+            map: source.grid(input)?,
+            start: source.tile(input, 'S', "start")?,
+            end: source.tile(input, 'E', "end")?,
+        })
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        find_paths(input).as_ref().and_then(part_1).into()
+    }
 
-    let paths_t1 = find_paths(&test_1);
-    let paths_t2 = find_paths(&test_2);
-    let paths_in = find_paths(&input);
+    fn part_2(input: &Self::Input) -> Answer {
+        find_paths(input).as_ref().map(part_2).into()
+    }
 
-    println!("First part test 1 answer: {} == 7036", part_1(&paths_t1));
-    println!("First part test 2 answer: {} == 11048", part_1(&paths_t2));
-    println!("First part answer: {}", part_1(&paths_in));
+    fn generate(generator: &mut Generator) -> Generated {
+        let side = generator.size() + 2;
+        let mut maze = generator.maze(side, side);
+        // Loops give the reindeer several best paths to choose from
+        maze.knock_down(generator, side);
+        let mut map = maze.map;
+        let last = 2 * side as i32 - 1;
+        map[Point::new(1, last)] = 'S';
+        map[Point::new(last, 1)] = 'E';
+        Generated::new(map.to_string() + "\n")
+    }
 
-    println!("Second part test 1 answer: {} == 45", part_2(&paths_t1));
-    println!("Second part test 2 answer: {} == 64", part_2(&paths_t2));
-    println!("Second part answer: {}", part_2(&paths_in));
+    fn render(input: &Self::Input, part: Part) -> Option<Canvas> {
+        let paths = find_paths(input)?;
+        let canvas = Canvas::new(input.map.clone());
+        Some(match part {
+            Part::One => {
+                // The way the reindeer faces when it leaves each tile
+                let facing: HashMap<Point, Direction> = paths
+                    .explored
+                    .path_to(&paths.ends[0])?
+                    .into_iter()
+                    .collect();
+                Direction::ORTHOGONAL
+                    .iter()
+                    .fold(canvas, |canvas, &direction| {
+                        canvas.mark(
+                            "best path",
+                            Color::Green,
+                            direction.glyph().unwrap(),
+                            facing
+                                .iter()
+                                .filter(|&(_, &facing)| facing == direction)
+                                .map(|(&point, _)| point),
+                        )
+                    })
+            }
+            Part::Two => canvas.mark(
+                "seat on a best path",
+                Color::Green,
+                'O',
+                paths
+                    .explored
+                    .on_optimal_paths(paths.ends.iter().copied())
+                    .into_iter()
+                    .map(|(point, _)| point),
+            ),
+        })
+    }
 }
 
-fn part_1(input: &Vec<Path>) -> usize {
-    input
-        .iter()
-        .map(|path| path.score())
-        .sorted()
-        .next()
-        .unwrap()
+fn part_1(paths: &Paths) -> Option<usize> {
+    paths.explored.distance(paths.ends.first()?)
 }
 
-fn part_2(input: &Vec<Path>) -> usize {
-    let best_score = part_1(input);
-    input
+fn part_2(paths: &Paths) -> usize {
+    paths
+        .explored
+        .on_optimal_paths(paths.ends.iter().copied())
         .iter()
-        .filter(|path| path.score() == best_score)
-        .fold(HashSet::new(), |mut a, f| {
-            a.extend(f.visited.clone());
-            a
-        })
+        .map(|&(point, _)| point)
+        .collect::<HashSet<_>>()
         .len()
 }
 
-fn find_position_of(input: &Vec<Vec<char>>, target: char) -> Option<(usize, usize)> {
-    input
-        .iter()
-        .enumerate()
-        .map(|(y, row)| row.iter().enumerate().map(move |(x, c)| (x, y, *c)))
+/// The cheapest ways from the start to the end, if there are any
+fn find_paths(maze: &Maze) -> Option<Paths> {
+    let (input, start, end) = (&maze.map, maze.start, maze.end);
+
+    // Stepping forward costs 1 and turning a quarter costs 1000
+    let successors = |&(point, facing): &Reindeer| {
+        let forward = point.step(facing);
+        [
+            // Off the map is as good as a wall, for mazes without walls around
+            (input.get(forward).is_some_and(|&tile| tile != '#')).then_some(((forward, facing), 1)),
+            Some(((point, facing.turn_left()), 1000)),
+            Some(((point, facing.turn_right()), 1000)),
+        ]
+        .into_iter()
         .flatten()
-        .filter_map(|(x, y, c)| if c == target { Some((x, y)) } else { None })
-        .next()
-}
+    };
+    let explored = search::dijkstra([(start, Direction::East)], successors, |&(point, _)| {
+        point == end
+    });
 
-pub fn find_paths(input: &Vec<Vec<char>>) -> Vec<Path> {
-    let start = find_position_of(input, 'S').unwrap();
-    let end = find_position_of(input, 'E').unwrap();
-    let directions = vec![(1, 0), (0, 1), (-1, 0), (0, -1)];
-
-    let mut paths: Vec<Path> = Vec::new();
-    let mut best_visited: HashMap<((usize, usize), (usize, usize)), usize> = HashMap::new();
-    let mut queue = VecDeque::from(vec![Path {
-        visited: vec![start],
-        last_direction: (1, 0),
-        turns: 0,
-    }]);
-
-    while let Some(unfinished) = queue.pop_front() {
-        let &last_position = unfinished.visited.last().unwrap();
-
-        for (x, y, d) in directions.iter().map(|&(dx, dy)| {
-            (
-                (last_position.0 as i32 + dx) as usize,
-                (last_position.1 as i32 + dy) as usize,
-                (dx, dy),
-            )
-        }) {
-            if unfinished.visited.contains(&(x, y)) {
-                continue;
-            }
+    let best = Direction::ORTHOGONAL
+        .iter()
+        .filter_map(|&facing| explored.distance(&(end, facing)))
+        .min()?;
+    let ends = Direction::ORTHOGONAL
+        .iter()
+        .map(|&facing| (end, facing))
+        .filter(|state| explored.distance(state) == Some(best))
+        .collect();
+    Some(Paths { explored, ends })
+}
 
-            let turned = if unfinished.last_direction == d { 0 } else { 1 };
-
-            match input[y][x] {
-                '.' => {
-                    let new = Path {
-                        visited: unfinished
-                            .visited
-                            .iter()
-                            .chain([&(x, y)])
-                            .cloned()
-                            .collect(),
-                        last_direction: d,
-                        turns: unfinished.turns + turned,
-                    };
-
-                    let k = (last_position, (x, y));
-                    if new.score() <= *best_visited.entry(k).or_insert(usize::MAX) {
-                        best_visited.insert(k, new.score());
-                        queue.push_back(new);
-                    }
-                }
-                'E' => {
-                    assert_eq!((x, y), end);
-                    paths.push(Path {
-                        visited: unfinished
-                            .visited
-                            .iter()
-                            .chain([&(x, y)])
-                            .cloned()
-                            .collect(),
-                        last_direction: d,
-                        turns: unfinished.turns + turned,
-                    });
-                }
-                _ => continue,
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_examples() {
+        assert_eq!(
+            part_1(&find_paths(&Day16::parse(&read_example(16, "test.1")).unwrap()).unwrap()),
+            Some(7036)
+        );
+        assert_eq!(
+            part_1(&find_paths(&Day16::parse(&read_example(16, "test.2")).unwrap()).unwrap()),
+            Some(11048)
+        );
     }
 
-    paths
-}
+    #[test]
+    fn part_2_examples() {
+        assert_eq!(
+            part_2(&find_paths(&Day16::parse(&read_example(16, "test.1")).unwrap()).unwrap()),
+            45
+        );
+        assert_eq!(
+            part_2(&find_paths(&Day16::parse(&read_example(16, "test.2")).unwrap()).unwrap()),
+            64
+        );
+    }
 
-pub fn read_input(file_path: &str) -> Vec<Vec<char>> {
-    read_to_string(file_path)
-        .unwrap()
-        .trim()
-        .split("\n")
-        .map(|row| row.chars().collect())
-        .collect()
+    #[test]
+    fn open_and_closed_mazes() {
+        // No walls around the maze, and a wall between the start and the end
+        let open = Day16::parse("S.\n.E\n").unwrap();
+        assert_eq!(Day16::part_1(&open), 1002.into());
+        let closed = Day16::parse("S#E\n").unwrap();
+        assert_eq!(Day16::part_1(&closed), Answer::None);
+        assert_eq!(Day16::part_2(&closed), Answer::None);
+    }
 }
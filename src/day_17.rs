@@ -1,33 +1,65 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    fs::read_to_string,
-};
+use std::collections::{HashMap, VecDeque};
 
 use itertools::Itertools;
-use regex::Regex;
+
+use crate::{
+    generate::{Generated, Generator},
+    parse::Source,
+    Answer, Result, Solution,
+};
 
 type RegisterType = u64;
 type OperandType = u8;
 
-fn main() {
-    let test_1 = read_input("./input/day_17.test.1.txt");
-    let test_2 = read_input("./input/day_17.test.2.txt");
-    let input = read_input("./input/day_17.txt");
-
-    println!(
-        "1st part test: {:?}",
-        run_program(&test_1.0, &test_1.1).iter().join(",")
-    );
-    println!(
-        "1st part: {:?}",
-        run_program(&input.0, &input.1).iter().join(",")
-    );
-
-    println!("2nd part test: {:?} == 117440", crack_program(&test_2.1));
-    println!("2nd part: {:?}", crack_program(&input.1));
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+
+    type Input = (HashMap<char, RegisterType>, Vec<OperandType>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_input(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        run_program(&input.0, &input.1).iter().join(",").into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        crack_program(&input.1).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        // Programs shaped like the puzzle's: each loop outputs a mix of the low
+        // bits of A and the bits above them, then shifts A by three bits
+        let program = [
+            2,
+            4,
+            1,
+            generator.number(0..8),
+            7,
+            5,
+            4,
+            generator.number(0..8),
+            1,
+            generator.number(0..8),
+            0,
+            3,
+            5,
+            5,
+            3,
+            0,
+        ];
+        let a: RegisterType = generator.number(1..1 << (3 * generator.size().min(16)));
+        Generated::new(format!(
+            "Register A: {a}\nRegister B: 0\nRegister C: 0\n\nProgram: {}\n",
+            program.iter().join(",")
+        ))
+    }
 }
 
-fn crack_program(program: &Vec<OperandType>) -> Option<RegisterType> {
+fn crack_program(program: &[OperandType]) -> Option<RegisterType> {
     let mut queue: VecDeque<(usize, RegisterType)> = VecDeque::new();
     queue.push_back((program.len() - 1, 0));
 
@@ -59,13 +91,13 @@ fn parse_operand(registers: &HashMap<char, RegisterType>, operand: OperandType)
         4 => registers[&'A'],
         5 => registers[&'B'],
         6 => registers[&'C'],
-        e => panic!("Invalid operand: {e}"),
+        _ => unreachable!("combo operands are checked when parsing"),
     }
 }
 
 fn run_program(
     registers: &HashMap<char, RegisterType>,
-    program: &Vec<OperandType>,
+    program: &[OperandType],
 ) -> Vec<OperandType> {
     let mut registers = registers.clone();
     let mut program_pointer = 0;
@@ -75,24 +107,26 @@ fn run_program(
         let operand = program[program_pointer + 1];
         let mut move_pointer = true;
 
-        let value = parse_operand(&registers, operand);
+        // Only some instructions read their operand as a combo operand
+        let literal = operand as RegisterType;
+        let combo = || parse_operand(&registers, operand);
         match op_code {
             0 => {
                 // adv
-                registers.insert('A', registers[&'A'] / RegisterType::pow(2, value as u32));
+                registers.insert('A', halve(registers[&'A'], combo()));
             }
             1 => {
                 // bxl
-                registers.insert('B', registers[&'B'] ^ value);
+                registers.insert('B', registers[&'B'] ^ literal);
             }
             2 => {
                 // bst
-                registers.insert('B', value % 8);
+                registers.insert('B', combo() % 8);
             }
             3 => {
                 // jnz
                 if registers[&'A'] != 0 {
-                    program_pointer = value as usize;
+                    program_pointer = literal as usize;
                     move_pointer = false;
                 }
             }
@@ -102,17 +136,17 @@ fn run_program(
             }
             5 => {
                 // out
-                output.push((value % 8) as OperandType);
+                output.push((combo() % 8) as OperandType);
             }
             6 => {
                 // bdv
-                registers.insert('B', registers[&'A'] / RegisterType::pow(2, value as u32));
+                registers.insert('B', halve(registers[&'A'], combo()));
             }
             7 => {
                 // cdv
-                registers.insert('C', registers[&'A'] / RegisterType::pow(2, value as u32));
+                registers.insert('C', halve(registers[&'A'], combo()));
             }
-            e => panic!("Unknown opcode: {e}"),
+            _ => unreachable!("opcodes are checked when parsing"),
         }
         if move_pointer {
             program_pointer += 2;
@@ -121,22 +155,116 @@ fn run_program(
     output
 }
 
-fn read_input(file_path: &str) -> (HashMap<char, RegisterType>, Vec<OperandType>) {
-    let register_pattern = Regex::new(r"Register (\w): (\d+)").unwrap();
-    let program_pattern = Regex::new(r"Program: ((\d+,?)+)").unwrap();
+/// `value` divided by two to the power of `times`, which may be more than the
+/// bits of a register
+fn halve(value: RegisterType, times: RegisterType) -> RegisterType {
+    u32::try_from(times)
+        .ok()
+        .and_then(|times| value.checked_shr(times))
+        .unwrap_or(0)
+}
+
+fn read_input(input: &str) -> Result<(HashMap<char, RegisterType>, Vec<OperandType>)> {
+    let [registers, program] = Source::new(input).split_sections(["registers", "program"])?;
+
+    let mut lines = registers.lines();
+    let registers = ['A', 'B', 'C']
+        .into_iter()
+        .map(|id| {
+            let template = format!("Register {id}: {{}}");
+            let line = lines.next().ok_or_else(|| registers.missing(&template))?;
+            let [value] = registers.fields(&template, line)?;
+            Ok((id, value))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let line = program
+        .lines()
+        .next()
+        .ok_or_else(|| program.missing("`Program: `"))?;
+    let tokens: Vec<&str> = program
+        .strip_prefix(line, "Program: ")?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    let numbers = tokens
+        .iter()
+        .map(|&token| match program.number(token)? {
+            number @ 0..8 => Ok(number),
+            _ => Err(program.unexpected(token, "a 3-bit number")),
+        })
+        .collect::<Result<Vec<OperandType>>>()?;
+    // Jumps only land on instructions, so checking them once is enough
+    for (instruction, tokens) in numbers.chunks(2).zip(tokens.chunks(2)) {
+        match *instruction {
+            [_] => return Err(program.missing("the operand of the last instruction")),
+            [0 | 2 | 5 | 6 | 7, 7] => {
+                return Err(program.unexpected(tokens[1], "a combo operand from 0 to 6"))
+            }
+            [3, address] if address % 2 == 1 => {
+                return Err(program.unexpected(tokens[1], "an even address to jump to"))
+            }
+            _ => {}
+        }
+    }
+    Ok((registers, numbers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        let (registers, program) = Day17::parse(&read_example(17, "test.1")).unwrap();
+        assert_eq!(
+            run_program(&registers, &program).iter().join(","),
+            "4,6,3,5,6,3,5,2,1,0"
+        );
+    }
 
-    let buffer = read_to_string(file_path).unwrap();
-    (
-        HashMap::from_iter(register_pattern.captures_iter(&buffer).map(|capture| {
-            let (_, [id, value]) = capture.extract();
+    #[test]
+    fn part_2_example() {
+        let (_, program) = Day17::parse(&read_example(17, "test.2")).unwrap();
+        assert_eq!(crack_program(&program), Some(117440));
+    }
+
+    #[test]
+    fn bad_programs() {
+        let registers = "Register A: 1\nRegister B: 0\nRegister C: 0\n\n";
+        for (program, error) in [
+            (
+                "0,1,5,8",
+                "line 5, column 16 (in program): unexpected `8`, expected a 3-bit number",
+            ),
+            (
+                "0,1,5,7",
+                "line 5, column 16 (in program): unexpected `7`, expected a combo operand from 0 to 6",
+            ),
+            (
+                "3,1",
+                "line 5, column 12 (in program): unexpected `1`, expected an even address to jump to",
+            ),
             (
-                id.chars().next().unwrap(),
-                value.parse::<RegisterType>().unwrap(),
-            )
-        })),
-        program_pattern.captures(&buffer).unwrap().extract::<2>().1[0]
-            .split(",")
-            .map(|s| s.parse::<OperandType>().unwrap())
-            .collect(),
-    )
+                "0,1,5",
+                "line 5, column 15 (in program): missing the operand of the last instruction",
+            ),
+        ] {
+            let input = format!("{registers}Program: {program}");
+            assert_eq!(Day17::parse(&input).unwrap_err().to_string(), error);
+        }
+        // Dividing by two to the power of more than the bits of a register
+        let input = format!("{registers}Program: 0,4,5,4");
+        let (mut registers, program) = Day17::parse(&input).unwrap();
+        registers.insert('A', 100);
+        assert_eq!(run_program(&registers, &program), [0]);
+    }
+
+    #[test]
+    fn literal_operands() {
+        // `bxl 7` then `out B`, where 7 would be no valid combo operand
+        let registers = HashMap::from([('A', 0), ('B', 0), ('C', 0)]);
+        assert_eq!(run_program(&registers, &[1, 7, 5, 5]), [7]);
+    }
 }
@@ -1,99 +1,255 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fs::read_to_string,
+use crate::{
+    animation::Recorder,
+    generate::{Generated, Generator},
+    grid::Grid,
+    params::{Param, Params},
+    parse::Source,
+    point::Point,
+    render::{Canvas, Color},
+    search, Answer, Part, Result, Solution,
 };
 
-use grid::Grid;
+/// The widest and tallest memory space, which keeps the maps it takes within
+/// a few megabytes
+const MAX_SIDE: usize = 1024;
 
-struct Path {
-    path: Vec<(usize, usize)>,
+/// The falling bytes, with the size of the memory space they fall in
+pub struct MemorySpace {
+    bytes: Vec<Point>,
+    size: (usize, usize),
+    fallen: usize,
 }
 
-fn main() {
-    let test = read_input("./input/day_18.test.txt");
-    let input = read_input("./input/day_18.txt");
-
-    println!(
-        "First part test answer: {} == 22",
-        part_1((7, 7), 12, &test)
-    );
-    println!("First part answer: {}", part_1((71, 71), 1024, &input),);
-
-    println!(
-        "Second part test answer: {:?} == (6, 1)",
-        part_2((7, 7), 12, &test),
-    );
-    println!("Second part answer: {:?}", part_2((71, 71), 1024, &input),);
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+
+    type Input = MemorySpace;
+
+    const PARAMS: &'static [Param] = &[
+        Param::new("width", "71", "width of the memory space"),
+        Param::new("height", "71", "height of the memory space"),
+        Param::new("fallen", "1024", "bytes fallen when the first part starts"),
+    ];
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        let bytes = read_input(input)?;
+        // Every byte falls inside the memory space
+        let (width, height) = bytes.iter().fold((1, 1), |(width, height), byte| {
+            (
+                width.max(byte.x as usize + 1),
+                height.max(byte.y as usize + 1),
+            )
+        });
+        Ok(MemorySpace {
+            size: (
+                params.get_within("width", width..=MAX_SIDE)?,
+                params.get_within("height", height..=MAX_SIDE)?,
+            ),
+            fallen: params.get_within("fallen", 0..=bytes.len())?,
+            bytes,
+        })
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input.size, input.fallen, &input.bytes).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input.size, input.fallen, &input.bytes)
+            .map(|blocker| blocker.to_string())
+            .into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let side = (generator.size() + 1).min(MAX_SIDE);
+        let end = Point::new(side as i32 - 1, side as i32 - 1);
+        // Every byte falls, so that the way out is cut off at last
+        let mut bytes: Vec<Point> = Grid::new(side, side, ())
+            .positions()
+            .filter(|&point| point != Point::ORIGIN && point != end)
+            .collect();
+        generator.shuffle(&mut bytes);
+        let blocker = part_2((side, side), 0, &bytes).unwrap();
+        let fallen = bytes.iter().position(|&byte| byte == blocker).unwrap() / 2;
+        let input = bytes.iter().map(|byte| format!("{},{}\n", byte.x, byte.y));
+        Generated::new(input.collect())
+            .with("width", side)
+            .with("height", side)
+            .with("fallen", fallen)
+    }
+
+    fn render(input: &Self::Input, part: Part) -> Option<Canvas> {
+        Some(match part {
+            Part::One => {
+                let path = get_path(input.size, input.fallen, &input.bytes)?;
+                Canvas::new(memory(input, input.fallen)).mark(
+                    "shortest path",
+                    Color::Green,
+                    'O',
+                    path,
+                )
+            }
+            Part::Two => {
+                let blocker = part_2(input.size, input.fallen, &input.bytes)?;
+                let fallen = input.bytes.iter().position(|&byte| byte == blocker)? + 1;
+                Canvas::new(memory(input, fallen)).mark(
+                    "first blocking byte",
+                    Color::Red,
+                    'X',
+                    [blocker],
+                )
+            }
+        })
+    }
+
+    fn animate(input: &Self::Input, part: Part, recorder: &mut Recorder) -> bool {
+        // The first part watches the bytes fall, the second one the shortest
+        // path dodging them until it is cut off
+        let fallen = match part {
+            Part::One => 0..=input.fallen,
+            Part::Two => {
+                // Until every byte has fallen, if none cuts off the way out
+                let last = part_2(input.size, input.fallen, &input.bytes)
+                    .and_then(|blocker| input.bytes.iter().position(|&byte| byte == blocker))
+                    .map_or(input.bytes.len(), |last| last + 1);
+                input.fallen..=last
+            }
+        };
+        let end = *fallen.end();
+        for fallen in fallen {
+            recorder.frame(|| {
+                let canvas = Canvas::new(memory(input, fallen));
+                match (part, get_path(input.size, fallen, &input.bytes)) {
+                    (Part::One, _) if fallen < end => canvas,
+                    (_, Some(path)) => canvas.mark("shortest path", Color::Green, 'O', path),
+                    (_, None) => canvas.highlight(
+                        "first blocking byte",
+                        Color::Red,
+                        [input.bytes[fallen - 1]],
+                    ),
+                }
+            });
+            if recorder.is_done() {
+                break;
+            }
+        }
+        true
+    }
+}
+
+/// The memory space once the first `fallen` bytes are in it
+fn memory(input: &MemorySpace, fallen: usize) -> Grid<char> {
+    let mut map = Grid::new(input.size.0, input.size.1, '.');
+    for &byte in &input.bytes[..fallen] {
+        map[byte] = '#';
+    }
+    map
 }
 
-fn part_1(boundaries: (usize, usize), size: usize, input: &Vec<(usize, usize)>) -> usize {
-    get_path(boundaries, size, input)
-        .iter()
-        .map(|p| p.path.len() - 2)
-        .next()
-        .unwrap()
+fn part_1(boundaries: (usize, usize), size: usize, input: &[Point]) -> Option<usize> {
+    get_path(boundaries, size, input).map(|path| path.len() - 1)
 }
 
-fn part_2(boundaries: (usize, usize), size: usize, input: &Vec<(usize, usize)>) -> (usize, usize) {
+/// The first byte after the first `size` that cuts off the way out, if any
+fn part_2(boundaries: (usize, usize), size: usize, input: &[Point]) -> Option<Point> {
     let mut new_size = size + 1;
     while let Some(valid_path) = get_path(boundaries, new_size, input) {
-        while !valid_path.path.contains(&input[new_size - 1]) {
+        while !valid_path.contains(input.get(new_size - 1)?) {
             new_size += 1;
         }
     }
-    input[new_size - 1]
+    input.get(new_size - 1).copied()
 }
 
-fn get_path(boundaries: (usize, usize), size: usize, input: &Vec<(usize, usize)>) -> Option<Path> {
-    let mut map: Grid<char> = Grid::init(boundaries.0, boundaries.1, '.');
-    for &(x, y) in input[..size].iter() {
-        map[(y, x)] = '#';
+/// Shortest path from the top left corner to the bottom right one, once the
+/// first `size` bytes have fallen
+fn get_path(boundaries: (usize, usize), size: usize, input: &[Point]) -> Option<Vec<Point>> {
+    let mut map: Grid<char> = Grid::new(boundaries.0, boundaries.1, '.');
+    for &position in input.iter().take(size) {
+        map[position] = '#';
     }
-    let directions = [(0, 1), (-1, 0), (0, -1), (1, 0)];
-    let end = (boundaries.0 - 1, boundaries.1 - 1);
-
-    let mut queue = VecDeque::from(vec![Path { path: vec![(0, 0)] }]);
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
-    while let Some(next) = queue.pop_front() {
-        let &(x, y) = next.path.iter().last().unwrap();
+    let end = Point::new(boundaries.0 as i32 - 1, boundaries.1 as i32 - 1);
 
-        if (x, y) == end {
-            return Some(Path {
-                path: next.path.clone().into_iter().chain([end]).collect(),
-            });
-        }
-        for (dx, dy) in directions {
-            let (i, j): (usize, usize) = ((x as i32 + dx) as usize, (y as i32 + dy) as usize);
-
-            match map.get(j, i) {
-                Some(c) => match c {
-                    '#' => {}
-                    _ => {
-                        if !visited.contains(&(i, j)) {
-                            visited.insert((i, j));
-                            queue.push_back(Path {
-                                path: next.path.clone().into_iter().chain([(i, j)]).collect(),
-                            });
-                        }
-                    }
-                },
-                None => {}
-            }
-        }
-    }
-    None
+    let successors = |&position: &Point| {
+        map.neighbors(position)
+            .filter(|(_, &c)| c != '#')
+            .map(|(neighbor, _)| neighbor)
+            .collect::<Vec<_>>()
+    };
+    search::bfs([Point::ORIGIN], successors, |&position| position == end).path_to(&end)
 }
 
-fn read_input(file_path: &str) -> Vec<(usize, usize)> {
-    read_to_string(file_path)
-        .unwrap()
-        .split("\n")
-        .filter_map(|f| {
-            if f.len() < 1 {
-                return None;
-            }
-            let mut s = f.trim().split(",").map(|s| s.parse::<usize>().unwrap());
-            Some((s.next().unwrap(), s.next().unwrap()))
+fn read_input(input: &str) -> Result<Vec<Point>> {
+    let source = Source::new(input);
+    source
+        .lines()
+        .map(|line| {
+            let [x, y]: [u16; 2] = source.fields("{},{}", line.trim())?;
+            Ok(Point::new(x.into(), y.into()))
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            part_1((7, 7), 12, &read_input(&read_example(18, "test")).unwrap()),
+            Some(22)
+        );
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(
+            part_2((7, 7), 12, &read_input(&read_example(18, "test")).unwrap()),
+            Some(Point::new(6, 1))
+        );
+    }
+
+    #[test]
+    fn parameters_fit_the_input() {
+        let input = read_example(18, "test");
+        let params = |assignments: &[(&str, &str)]| {
+            let mut params = Params::defaults(Day18::PARAMS);
+            for &(name, value) in [("width", "7"), ("height", "7")].iter().chain(assignments) {
+                params.set(name, value);
+            }
+            Day18::parse_with(&input, &params)
+        };
+        for (assignment, error) in [
+            (
+                ("width", "3"),
+                "bad parameter `width`: `3` is out of range, expected from 7 to 1024",
+            ),
+            (
+                ("height", "100000"),
+                "bad parameter `height`: `100000` is out of range, expected from 7 to 1024",
+            ),
+            (
+                ("fallen", "30"),
+                "bad parameter `fallen`: `30` is out of range, expected from 0 to 25",
+            ),
+        ] {
+            let Err(refused) = params(&[assignment]) else {
+                panic!("{assignment:?} was accepted");
+            };
+            assert_eq!(refused.to_string(), error);
+        }
+        // Once every byte has fallen, none is left to cut off the way out
+        let Ok(memory) = params(&[("fallen", "25")]) else {
+            panic!("every byte falling was refused");
+        };
+        assert_eq!(part_2(memory.size, memory.fallen, &memory.bytes), None);
+    }
+}
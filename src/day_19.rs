@@ -1,67 +1,149 @@
-use std::fs::read_to_string;
-
-use cached::proc_macro::cached;
-use cached::SizedCache;
 use itertools::Itertools;
-use regex::Regex;
+use log::debug;
+
+use crate::{
+    generate::{Generated, Generator},
+    memo::Memo,
+    parse::Source,
+    Answer, Result, Solution,
+};
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+
+    type Input = (Vec<String>, Vec<String>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_input(input)
+    }
 
-fn main() {
-    let test = read_input("./input/day_19.test.txt");
-    let input = read_input("./input/day_19.txt");
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(&input.1, &input.0).into()
+    }
 
-    println!("First part test answer: {} == 6", part_1(&test.1, &test.0));
-    println!("First part answer: {}", part_1(&input.1, &input.0));
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(&input.1, &input.0).into()
+    }
 
-    println!("Second part test answer: {} == 19", part_2(&test.1, &test.0));
-    println!("Second part answer: {}", part_2(&input.1, &input.0));
+    fn generate(generator: &mut Generator) -> Generated {
+        let mut patterns = Vec::new();
+        while patterns.len() < generator.size() + 3 {
+            let length = generator.number(1..=3);
+            let pattern = generator.word("wubrg", length);
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+        // Most designs are made of the patterns, the others are most likely not
+        let designs = (0..generator.size()).map(|_| match generator.chance(0.6) {
+            true => (0..generator.number(2..=6))
+                .map(|_| generator.pick(&patterns).clone())
+                .collect::<String>(),
+            false => {
+                let length = generator.number(4..=10);
+                generator.word("wubrg", length)
+            }
+        });
+        Generated::new(format!(
+            "{}\n\n{}\n",
+            patterns.join(", "),
+            designs.collect_vec().join("\n")
+        ))
+    }
 }
 
-fn part_1(input: &Vec<String>, materials: &Vec<String>) -> usize {
-    input
+fn part_1(input: &[String], materials: &[String]) -> usize {
+    let mut memo = Memo::new();
+    let possible = input
         .iter()
-        .filter(|&order| can_make(order, materials) > 0)
-        .count()
+        .filter(|&order| can_make(order, materials, &mut memo) > 0)
+        .count();
+    debug!("can_make memo: {}", memo.stats());
+    possible
 }
 
-fn part_2(input: &Vec<String>, materials: &Vec<String>) -> usize {
-    input.iter().map(|order| can_make(order, materials)).sum()
+fn part_2(input: &[String], materials: &[String]) -> usize {
+    let mut memo = Memo::new();
+    let arrangements = input
+        .iter()
+        .map(|order| can_make(order, materials, &mut memo))
+        .sum();
+    debug!("can_make memo: {}", memo.stats());
+    arrangements
 }
 
-#[cached(
-    ty = "SizedCache<String, usize>",
-    create = "{ SizedCache::with_size(256) }",
-    convert = r#"{ format!("{}", order) }"#,
-)]
-fn can_make(order: &str, materials: &Vec<String>) -> usize {
-    if order.len() < 1 {
+/// Ways to make `order` out of `materials`. The memo must only ever have
+/// seen the same materials.
+fn can_make<'a>(order: &'a str, materials: &[String], memo: &mut Memo<&'a str, usize>) -> usize {
+    if order.is_empty() {
         return 1;
     }
-    materials.iter().filter_map(
-        |pattern| {
-            match order.starts_with(pattern) {
+    memo.get_or_compute(order, |memo| {
+        materials
+            .iter()
+            .filter_map(|pattern| match order.starts_with(pattern) {
                 false => None,
-                true => Some(can_make(&order[pattern.len()..], materials))
-            }
-        }
-    ).sum()
+                true => Some(can_make(&order[pattern.len()..], materials, memo)),
+            })
+            .sum()
+    })
 }
 
-fn read_input(file_path: &str) -> (Vec<String>, Vec<String>) {
-    let break_pattern = Regex::new(r"(\r?\n){2,}").unwrap();
-    let string = read_to_string(file_path).unwrap();
-    let parts: Vec<&str> = break_pattern.split(&string).collect();
+fn read_input(input: &str) -> Result<(Vec<String>, Vec<String>)> {
+    let [patterns, designs] =
+        Source::new(input).split_sections(["towel patterns", "towel designs"])?;
 
-    (
-        parts[0]
-            .trim()
-            .split(", ")
-            .map(|s| s.to_string())
+    Ok((
+        patterns
+            .text()
+            .split(',')
+            .map(|s| match s.trim() {
+                // An empty pattern would make any design out of nothing, forever
+                "" => Err(patterns.unexpected(s.trim(), "a towel pattern")),
+                pattern => Ok(pattern.to_string()),
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
             .sorted_by(|a, b| Ord::cmp(&b.len(), &a.len()))
             .collect(),
-        parts[1]
-            .trim()
-            .split("\n")
-            .map(|s| s.trim().to_string())
-            .collect(),
-    )
+        designs.lines().map(|s| s.trim().to_string()).collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        let (materials, designs) = Day19::parse(&read_example(19, "test")).unwrap();
+        assert_eq!(part_1(&designs, &materials), 6);
+    }
+
+    #[test]
+    fn part_2_example() {
+        let (materials, designs) = Day19::parse(&read_example(19, "test")).unwrap();
+        assert_eq!(part_2(&designs, &materials), 16);
+    }
+
+    #[test]
+    fn inputs_do_not_share_results() {
+        let designs = ["rrr".to_owned()];
+        assert_eq!(part_2(&designs, &["r".to_owned()]), 1);
+        assert_eq!(part_2(&designs, &["r".to_owned(), "rr".to_owned()]), 3);
+    }
+
+    #[test]
+    fn empty_patterns() {
+        let Err(error) = Day19::parse("r, b,\n\nrrb\n") else {
+            panic!("a trailing comma parsed");
+        };
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 6 (in towel patterns): unexpected ``, expected a towel pattern"
+        );
+    }
 }
@@ -1,36 +1,60 @@
-mod utilities;
-use utilities::read_lines;
+use itertools::Itertools;
 
-fn main() {
-    let mut reports: Vec<Vec<i32>> = Vec::new();
-    if let Ok(lines) = read_lines("./input/day_2.txt") {
-        for line in lines.flatten() {
-            if line.trim().is_empty() {
-                continue;
-            }
-            let numbers = line
-                .split(" ")
-                .map(|x| str::parse::<i32>(x).unwrap())
-                .collect::<Vec<i32>>();
-            reports.push(numbers);
-        }
+use crate::{
+    generate::{Generated, Generator},
+    parse::Source,
+    Answer, Result, Solution,
+};
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_input(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
     }
-    println!("List 1 length: {}", reports.len());
 
-    let mut safe_reports_1: usize = 0;
-    let mut unsafe_reports: Vec<&Vec<i32>> = Vec::new();
-    for i in 0..reports.len() {
-        let report = &reports[i];
-        if is_safe(&report) {
-            safe_reports_1 += 1;
-        } else {
-            unsafe_reports.push(report);
+    fn generate(generator: &mut Generator) -> Generated {
+        let mut input = String::new();
+        for _ in 0..generator.size() {
+            let direction = *generator.pick(&[-1, 1]);
+            let mut level: i32 = generator.number(40..=70);
+            let mut report = vec![level];
+            for _ in 1..generator.number(5..=8) {
+                // Mostly safe steps, with the odd one too big, flat or backwards
+                level += match generator.chance(0.1) {
+                    true => generator.number(-5..=5),
+                    false => direction * generator.number(1..=3),
+                };
+                report.push(level);
+            }
+            input.push_str(&report.iter().join(" "));
+            input.push('\n');
         }
+        Generated::new(input)
     }
+}
+
+fn part_1(reports: &[Vec<i32>]) -> usize {
+    reports.iter().filter(|report| is_safe(report)).count()
+}
+
+fn part_2(reports: &[Vec<i32>]) -> usize {
+    let unsafe_reports: Vec<&Vec<i32>> = reports.iter().filter(|report| !is_safe(report)).collect();
 
     let mut safe_reports_2: usize = 0;
-    for i in 0..unsafe_reports.len() {
-        let report = &reports[i];
+    for report in unsafe_reports {
         for j in 0..report.len() {
             let mut modified = report.clone();
             modified.remove(j);
@@ -40,13 +64,10 @@ fn main() {
             }
         }
     }
-
-    println!("First part answer: {}", safe_reports_1);
-    println!("Second part answer: {}", safe_reports_1 + safe_reports_2);
+    part_1(reports) + safe_reports_2
 }
 
-fn is_safe(numbers: &Vec<i32>) -> bool 
-{
+fn is_safe(numbers: &[i32]) -> bool {
     let count = numbers.len() - 1;
     let mut deltas: Vec<i32> = Vec::with_capacity(count);
     for j in 0..count {
@@ -54,11 +75,20 @@ fn is_safe(numbers: &Vec<i32>) -> bool
     }
 
     let problems = (0..deltas.len())
-        .into_iter()
         .map(|i| {
-            !(1 <= deltas[i].abs() && deltas[i].abs() <= 3 && deltas[i].signum() == deltas[0].signum())
+            !(1 <= deltas[i].abs()
+                && deltas[i].abs() <= 3
+                && deltas[i].signum() == deltas[0].signum())
         })
         .filter(|x| *x)
         .count();
-    return problems == 0;
+    problems == 0
+}
+
+fn read_input(input: &str) -> Result<Vec<Vec<i32>>> {
+    let source = Source::new(input);
+    source
+        .lines()
+        .map(|line| source.numbers(line, &[' ']))
+        .collect()
 }
@@ -1,54 +1,121 @@
-mod day_16;
-
 use std::collections::HashMap;
 
-use day_16::{find_paths, read_input, Path};
-use itertools::Itertools;
 use rayon::prelude::*;
 
-fn main() {
-    let test = read_input("./input/day_20.test.txt");
-    let input = read_input("./input/day_20.txt");
+use crate::{
+    generate::{Generated, Generator},
+    grid::Grid,
+    params::{Param, Params},
+    parse::Source,
+    point::Point,
+    render::{Canvas, Color},
+    search, Answer, Part, Result, Solution,
+};
+
+/// The map of the race, its start and end, and how much time a cheat must
+/// save to count
+pub struct Race {
+    map: Grid<char>,
+    start: Point,
+    end: Point,
+    min_saving: usize,
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+
+    type Input = Race;
+
+    const PARAMS: &'static [Param] = &[Param::new(
+        "min_saving",
+        "100",
+        "picoseconds a cheat must save to be counted",
+    )];
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        let source = Source::new(input);
+        Ok(Race {
+            map: source.grid(input)?,
+            start: source.tile(input, 'S', "start")?,
+            end: source.tile(input, 'E', "end")?,
+            min_saving: params.get("min_saving")?,
+        })
+    }
 
-    // Challenge unit-tests
-    let test_paths = find_paths(&test);
-    let test_path = test_paths.iter().next().unwrap();
+    fn part_1(input: &Self::Input) -> Answer {
+        race_track(input)
+            .map(|track| part_1(&track, input.min_saving).values().sum::<i32>())
+            .into()
+    }
 
-    let map = part_1(&test_path, 0);
-    println!("First part test answer:",);
-    for key in map.keys().sorted() {
-        println!("- {key}: {}", map[key]);
+    fn part_2(input: &Self::Input) -> Answer {
+        race_track(input)
+            .map(|track| part_2(&track, input.min_saving).values().sum::<i32>())
+            .into()
     }
 
-    let map = part_2(&test_path, 50);
-    println!("Second part test answer:",);
-    for key in map.keys().sorted() {
-        println!("- {key}: {}", map[key]);
+    fn generate(generator: &mut Generator) -> Generated {
+        // The track is the only way through a maze, from one corner to the
+        // room furthest from it
+        let side = generator.size() + 2;
+        let maze = generator.maze(side, side);
+        let track = maze.path_to(maze.deepest_room());
+        let mut map = Grid::new(maze.map.width(), maze.map.height(), '#');
+        for &point in &track {
+            map[point] = '.';
+        }
+        map[track[0]] = 'S';
+        map[track[track.len() - 1]] = 'E';
+        Generated::new(map.to_string() + "\n").with("min_saving", (track.len() / 4).max(2))
     }
 
-    // Challenge input
-    let input_paths = find_paths(&input);
-    let input_path = input_paths.iter().next().unwrap();
-    let map = part_1(&input_path, 100);
-    println!("First part answer: {}", map.values().sum::<i32>());
-    let map = part_2(&input_path, 100);
-    println!("Second part answer: {}", map.values().sum::<i32>());
+    fn render(input: &Self::Input, part: Part) -> Option<Canvas> {
+        let track = race_track(input)?;
+        let max_length = match part {
+            Part::One => 2,
+            Part::Two => 20,
+        };
+        let (start, end, _) = find_cheat_points(&track, max_length)
+            .into_iter()
+            .max_by_key(|&(i, j, l)| j - i - l)?;
+        Some(
+            Canvas::new(input.map.clone())
+                .highlight("track", Color::Blue, track.iter().copied())
+                .mark("best cheat start", Color::Red, '1', [track[start]])
+                .mark("best cheat end", Color::Red, '2', [track[end]]),
+        )
+    }
 }
 
-fn manhattan(a: &(usize, usize), b: &(usize, usize)) -> usize {
-    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+/// Every tile of the track, in order from the start to the end, unless the
+/// end cannot be reached
+fn race_track(race: &Race) -> Option<Vec<Point>> {
+    let (map, start, end) = (&race.map, race.start, race.end);
+    let successors = |&point: &Point| {
+        map.neighbors(point)
+            .filter(|(_, &c)| c != '#')
+            .map(|(next, _)| next)
+            .collect::<Vec<_>>()
+    };
+    search::bfs([start], successors, |&point| point == end).path_to(&end)
 }
 
-fn find_cheat_points(path: &Path, max_length: usize) -> Vec<(usize, usize, usize)> {
-    (0..path.visited.len())
+fn find_cheat_points(path: &[Point], max_length: usize) -> Vec<(usize, usize, usize)> {
+    (0..path.len())
         .into_par_iter()
         .map(|i| {
-            let p = path.visited[i];
-            let tail = (i + 1)..path.visited.len();
+            let p = path[i];
+            let tail = (i + 1)..path.len();
             tail.into_iter()
                 .filter_map(move |j| {
-                    let o = path.visited[j];
-                    let l = manhattan(&p, &o);
+                    let o = path[j];
+                    let l = p.manhattan(o) as usize;
                     if l <= max_length {
                         Some((i, j, l))
                     } else {
@@ -61,7 +128,7 @@ fn find_cheat_points(path: &Path, max_length: usize) -> Vec<(usize, usize, usize
         .collect()
 }
 
-fn eval(path: &Path, max_length: usize, minimum: usize) -> HashMap<usize, i32> {
+fn eval(path: &[Point], max_length: usize, minimum: usize) -> HashMap<usize, i32> {
     find_cheat_points(path, max_length)
         .par_iter()
         .map(|(i, j, l)| j - i - l)
@@ -74,10 +141,68 @@ fn eval(path: &Path, max_length: usize, minimum: usize) -> HashMap<usize, i32> {
         })
 }
 
-fn part_1(path: &Path, minimum: usize) -> HashMap<usize, i32> {
+fn part_1(path: &[Point], minimum: usize) -> HashMap<usize, i32> {
     eval(path, 2, minimum)
 }
 
-fn part_2(path: &Path, minimum: usize) -> HashMap<usize, i32> {
+fn part_2(path: &[Point], minimum: usize) -> HashMap<usize, i32> {
     eval(path, 20, minimum)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        let track = race_track(&Day20::parse(&read_example(20, "test")).unwrap()).unwrap();
+        assert_eq!(
+            part_1(&track, 1),
+            HashMap::from([
+                (2, 14),
+                (4, 14),
+                (6, 2),
+                (8, 4),
+                (10, 2),
+                (12, 3),
+                (20, 1),
+                (36, 1),
+                (38, 1),
+                (40, 1),
+                (64, 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn part_2_example() {
+        let track = race_track(&Day20::parse(&read_example(20, "test")).unwrap()).unwrap();
+        assert_eq!(
+            part_2(&track, 50),
+            HashMap::from([
+                (50, 32),
+                (52, 31),
+                (54, 29),
+                (56, 39),
+                (58, 25),
+                (60, 23),
+                (62, 20),
+                (64, 19),
+                (66, 12),
+                (68, 14),
+                (70, 12),
+                (72, 22),
+                (74, 4),
+                (76, 3),
+            ])
+        );
+    }
+
+    #[test]
+    fn unreachable_end() {
+        let race = Day20::parse("S#E\n").unwrap();
+        assert_eq!(Day20::part_1(&race), Answer::None);
+        assert_eq!(Day20::part_2(&race), Answer::None);
+    }
+}
@@ -1,9 +1,17 @@
-use std::{collections::HashMap, fs::read_to_string};
+use std::collections::HashMap;
 
-use cached::proc_macro::cached;
-use cached::SizedCache;
 use itertools::Itertools;
 use lazy_static::lazy_static;
+use log::{debug, trace};
+
+use crate::{
+    arith,
+    generate::{Generated, Generator},
+    memo::Memo,
+    params::{Param, Params},
+    parse::Source,
+    Answer, Result, Solution,
+};
 
 type Keypad = HashMap<char, (i32, i32)>;
 
@@ -34,81 +42,156 @@ lazy_static! {
         ]) };
 }
 
-fn main() {
-    let test = read_input("./input/day_21.test.txt");
-    let input = read_input("./input/day_21.txt");
+/// Which keypad a robot presses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Pad {
+    Numeric,
+    Directional,
+}
 
-    println!("First part test answer: {} == 126384", part_1(&test));
-    println!("First part answer: {}", part_1(&input));
+impl Pad {
+    fn keys(self) -> &'static Keypad {
+        match self {
+            Pad::Numeric => &NUMPAD,
+            Pad::Directional => &DIRPAD,
+        }
+    }
+}
 
-    println!("Second part answer: {}", part_2(&input));
+/// What one solve remembers of the presses it worked out
+#[derive(Default)]
+struct Memos {
+    /// Fewest presses for a sequence at a depth, on a pad
+    presses: Memo<(Vec<char>, usize, Pad), usize>,
+    /// Shortest moves between two keys of a pad
+    paths: Memo<(char, char, Pad), Vec<Vec<char>>>,
 }
 
-fn part_1(codes: &Vec<Vec<char>>) -> usize {
-    eval(codes, 2)
+/// The most robots for which the complexities of any five codes of three
+/// digits, as in the puzzle, add up without overflowing. Other inputs may
+/// still overflow, which the `checked` feature reports.
+const MAX_ROBOTS: usize = 36;
+
+/// The door codes, with how many robots on directional keypads stand
+/// between us and the door in each part
+pub struct Door {
+    codes: Vec<Vec<char>>,
+    robots: (usize, usize),
 }
 
-fn part_2(codes: &Vec<Vec<char>>) -> usize {
-    eval(codes, 25)
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+
+    type Input = Door;
+
+    const PARAMS: &'static [Param] = &[
+        Param::new(
+            "part_1_robots",
+            "2",
+            "robots on directional keypads in the first part",
+        ),
+        Param::new(
+            "part_2_robots",
+            "25",
+            "robots on directional keypads in the second part",
+        ),
+    ];
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        Ok(Door {
+            codes: read_input(input)?,
+            robots: (
+                params.get_within("part_1_robots", 0..=MAX_ROBOTS)?,
+                params.get_within("part_2_robots", 0..=MAX_ROBOTS)?,
+            ),
+        })
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        eval(&input.codes, input.robots.0).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        eval(&input.codes, input.robots.1).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let codes = (0..generator.size()).map(|_| generator.word("0123456789", 3) + "A\n");
+        Generated::new(codes.collect())
+    }
 }
 
-fn eval(codes: &Vec<Vec<char>>, depth: usize) -> usize {
+fn eval(codes: &[Vec<char>], depth: usize) -> usize {
+    let mut memos = Memos::default();
     let sequences = codes
         .iter()
-        .map(|code| min_input_path(code, depth + 1, &NUMPAD))
+        .map(|code| min_input_path(code, depth + 1, Pad::Numeric, &mut memos))
         .zip(codes)
         .map(|(seq, code)| {
-            //println!("{:?}", seq.iter().collect::<String>());
+            trace!("{} takes {seq} presses", code.iter().collect::<String>());
             (
                 code.iter()
                     .filter(|c| c.is_numeric())
                     .collect::<String>()
                     .parse::<usize>()
-                    .unwrap(),
+                    .unwrap_or_else(|_| unreachable!("codes are checked when parsing")),
                 seq,
             )
         })
         .collect::<Vec<_>>();
-    sequences.iter().map(|(num, len)| num * len).sum::<usize>()
+    debug!("min_input_path memo: {}", memos.presses.stats());
+    debug!("input_paths memo: {}", memos.paths.stats());
+    sequences
+        .iter()
+        .fold(0, |sum, (num, len)| arith::add(&sum, &arith::mul(num, len)))
 }
 
-#[cached(
-    ty = "SizedCache<String, usize>",
-    create = "{ SizedCache::with_size(256) }",
-    convert = r#"{ format!("{:?}{}{:p}", sequence, depth, input) }"#
-)]
-fn min_input_path(sequence: &Vec<char>, depth: usize, input: &Keypad) -> usize {
-    if depth > 0 {
-        let mut previous = 'A';
-        let mut pieces = Vec::new();
-        for &c in sequence {
-            let paths = input_paths(previous, c, &input);
-            let best = paths
-                .iter()
-                .map(|path| {
-                    min_input_path(
-                        &path.into_iter().chain(vec!['A'].iter()).cloned().collect(),
-                        depth - 1,
-                        &DIRPAD,
-                    )
-                })
-                .min()
-                .unwrap();
-            pieces.push(best);
-            previous = c;
-        }
-
-        return pieces.into_iter().sum();
+fn min_input_path(sequence: &[char], depth: usize, pad: Pad, memos: &mut Memos) -> usize {
+    if depth == 0 {
+        return sequence.len();
+    }
+    let key = (sequence.to_vec(), depth, pad);
+    if let Some(presses) = memos.presses.get(&key) {
+        return presses;
+    }
+    let mut previous = 'A';
+    let mut presses = 0;
+    for &c in sequence {
+        let paths = input_paths(previous, c, pad, memos);
+        let fewest = paths
+            .iter()
+            .map(|path| {
+                min_input_path(
+                    &path.iter().chain(['A'].iter()).cloned().collect::<Vec<_>>(),
+                    depth - 1,
+                    Pad::Directional,
+                    memos,
+                )
+            })
+            .min()
+            .unwrap();
+        presses = arith::add(&presses, &fewest);
+        previous = c;
     }
-    return sequence.len();
+    memos.presses.insert(key, presses);
+    presses
 }
 
-#[cached(
-    ty = "SizedCache<String, Vec<Vec<char>>>",
-    create = "{ SizedCache::with_size(256) }",
-    convert = r#"{ format!("{}{}{:p}", from, to, input) }"#
-)]
-fn input_paths(from: char, to: char, input: &Keypad) -> Vec<Vec<char>> {
+fn input_paths(from: char, to: char, pad: Pad, memos: &mut Memos) -> Vec<Vec<char>> {
+    memos
+        .paths
+        .get_or_compute((from, to, pad), |_| moves(from, to, pad.keys()))
+}
+
+/// The shortest ways to move from one key to another, without going over the
+/// gap of the keypad
+fn moves(from: char, to: char, input: &Keypad) -> Vec<Vec<char>> {
     if from == to {
         return vec![vec![]];
     }
@@ -133,7 +216,7 @@ fn input_paths(from: char, to: char, input: &Keypad) -> Vec<Vec<char>> {
         })
         .collect::<Vec<_>>();
 
-    return if dx == 0 {
+    if dx == 0 {
         vec![moves_y]
     } else if dy == 0 {
         vec![moves_x]
@@ -146,24 +229,83 @@ fn input_paths(from: char, to: char, input: &Keypad) -> Vec<Vec<char>> {
             vec_concat(&moves_x, &moves_y),
             vec_concat(&moves_y, &moves_x),
         ]
-    };
+    }
 }
 
-fn vec_concat<T>(a: &Vec<T>, b: &Vec<T>) -> Vec<T>
+fn vec_concat<T>(a: &[T], b: &[T]) -> Vec<T>
 where
     T: Copy,
 {
-    a.into_iter()
-        .chain(b.into_iter())
-        .copied()
-        .collect::<Vec<T>>()
+    a.iter().chain(b).copied().collect::<Vec<T>>()
 }
 
-fn read_input(file_path: &str) -> Vec<Vec<char>> {
-    read_to_string(file_path)
-        .unwrap()
-        .trim()
-        .split("\n")
-        .map(|s| s.chars().collect())
+fn read_input(input: &str) -> Result<Vec<Vec<char>>> {
+    let source = Source::new(input);
+    source
+        .lines()
+        .map(|s| {
+            let code = s
+                .char_indices()
+                .map(|(offset, c)| match NUMPAD.contains_key(&c) {
+                    true => Ok(c),
+                    false => {
+                        Err(source.unexpected(&s[offset..offset + c.len_utf8()], "a numpad key"))
+                    }
+                })
+                .collect::<Result<Vec<char>>>()?;
+            // The complexity of a code multiplies by the number in it
+            let digits: String = code.iter().filter(|c| c.is_numeric()).collect();
+            match digits.parse::<usize>() {
+                Ok(_) => Ok(code),
+                Err(_) => Err(source.unexpected(s, "a code with a number that fits")),
+            }
+        })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            eval(&Day21::parse(&read_example(21, "test")).unwrap().codes, 2),
+            126384
+        );
+    }
+
+    #[test]
+    fn codes_without_numbers() {
+        for code in ["A", "99999999999999999999A"] {
+            let Err(error) = read_input(code) else {
+                panic!("{code} parsed");
+            };
+            assert_eq!(
+                error.to_string(),
+                format!("line 1, column 1: unexpected `{code}`, expected a code with a number that fits")
+            );
+        }
+    }
+
+    #[test]
+    fn most_robots() {
+        // The code of three digits with the most complexity, five times
+        let input = "957A\n".repeat(5);
+        let mut params = Params::defaults(Day21::PARAMS);
+        params.set("part_2_robots", MAX_ROBOTS.to_string());
+        let door = Day21::parse_with(&input, &params).unwrap();
+        let complexity = arith::catch_overflow(|| eval(&door.codes, door.robots.1));
+        assert_eq!(complexity.unwrap(), 10541225354819084640);
+
+        params.set("part_2_robots", (MAX_ROBOTS + 1).to_string());
+        let Err(refused) = Day21::parse_with(&input, &params) else {
+            panic!("more robots than checked were accepted");
+        };
+        assert_eq!(
+            refused.to_string(),
+            "bad parameter `part_2_robots`: `37` is out of range, expected from 0 to 36"
+        );
+    }
+}
@@ -1,54 +1,91 @@
 use std::{
     collections::{HashMap, VecDeque},
-    fs::read_to_string,
     hash::{DefaultHasher, Hash, Hasher},
 };
 
 use rayon::prelude::*;
 
-fn main() {
-    let test: Vec<u64> = vec![1, 10, 100, 2024];
-    let input = read_input("./input/day_22.txt");
+use crate::{
+    arith,
+    generate::{Generated, Generator},
+    params::{Param, Params},
+    parse::Source,
+    Answer, Result, Solution,
+};
+
+/// The initial secret number of every buyer, with how many new secret numbers
+/// each of them generates
+pub struct Market {
+    secrets: Vec<u64>,
+    rounds: usize,
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+
+    type Input = Market;
+
+    const PARAMS: &'static [Param] = &[Param::new(
+        "rounds",
+        "2000",
+        "new secret numbers each buyer generates",
+    )];
 
-    assert_eq!(15887950, find_secret(123, 1));
-    assert_eq!(16495136, find_secret(123, 2));
-    assert_eq!(527345, find_secret(123, 3));
-    assert_eq!(704524, find_secret(123, 4));
-    assert_eq!(1553684, find_secret(123, 5));
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        Ok(Market {
+            secrets: read_input(input)?,
+            rounds: params.get_within("rounds", 1..)?,
+        })
+    }
 
-    println!("First part test answer: {} == 37327623", part_1(&test));
-    println!("First part answer: {}", part_1(&input));
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(&input.secrets, input.rounds).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(&input.secrets, input.rounds).into()
+    }
 
-    println!("Second part test answer: {} == 24", part_2(&test));
-    println!("Second part answer: {}", part_2(&input));
+    fn generate(generator: &mut Generator) -> Generated {
+        let secrets = (0..generator.size()).map(|_| generator.number::<u64, _>(1..1 << 24));
+        Generated::new(secrets.map(|secret| format!("{secret}\n")).collect())
+    }
 }
 
-fn part_1(input: &Vec<u64>) -> u64 {
-    input.par_iter().map(|x| find_secret(*x, 2000)).sum()
+fn part_1(input: &[u64], rounds: usize) -> u64 {
+    input
+        .par_iter()
+        .map(|x| find_secret(*x, rounds))
+        .reduce(|| 0, |a, b| arith::add(&a, &b))
 }
 
 fn find_secret(x: u64, depth: usize) -> u64 {
     (0..depth).fold(x, |previous, _| next_secret_number(previous))
 }
 
-fn part_2(input: &Vec<u64>) -> u16 {
-    *input
+fn part_2(input: &[u64], rounds: usize) -> u64 {
+    input
         .par_iter()
-        .map(|x| record_price_changes(*x, 4, 2000))
+        .map(|x| record_price_changes(*x, 4, rounds))
         .collect::<Vec<_>>()
         .into_iter()
         .fold(HashMap::new(), |mut sum, seqs| {
-            for key in seqs.keys() {
-                let value = seqs[key];
-                sum.entry(key.clone())
-                    .and_modify(|v| *v += value)
-                    .or_insert(value);
+            for (key, price) in seqs {
+                let bananas = sum.entry(key).or_insert(0);
+                *bananas = arith::add(bananas, &u64::from(price));
             }
             sum
         })
-        .values()
+        .into_values()
         .max()
-        .unwrap()
+        // No buyer sees four price changes, so nothing can be sold
+        .unwrap_or(0)
 }
 
 fn record_price_changes(
@@ -85,20 +122,76 @@ fn record_price_changes(
 }
 
 fn next_secret_number(secret: u64) -> u64 {
-    let a = step(secret, secret * 64);
-    let b = step(a, (a as f64 / 32f64).floor() as u64);
-    step(b, b * 2048)
+    let a = step(secret, arith::mul(&secret, &64));
+    let b = step(a, a / 32);
+    step(b, arith::mul(&b, &2048))
 }
 
 fn step(a: u64, b: u64) -> u64 {
     (a ^ b) % 16777216
 }
 
-fn read_input(file_path: &str) -> Vec<u64> {
-    read_to_string(file_path)
-        .unwrap()
-        .trim()
-        .split("\n")
-        .map(|p| p.parse::<u64>().unwrap())
-        .collect()
+fn read_input(input: &str) -> Result<Vec<u64>> {
+    let source = Source::new(input);
+    let secrets: Vec<u64> = source
+        .lines()
+        .map(|p| source.number::<u64>(p))
+        .collect::<Result<_>>()?;
+    match secrets.is_empty() {
+        true => Err(source.missing("the secret numbers of the buyers")),
+        false => Ok(secrets),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn secret_numbers() {
+        assert_eq!(find_secret(123, 1), 15887950);
+        assert_eq!(find_secret(123, 2), 16495136);
+        assert_eq!(find_secret(123, 3), 527345);
+        assert_eq!(find_secret(123, 4), 704524);
+        assert_eq!(find_secret(123, 5), 1553684);
+    }
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            part_1(
+                &Day22::parse(&read_example(22, "test")).unwrap().secrets,
+                2000
+            ),
+            37327623
+        );
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(
+            part_2(
+                &Day22::parse(&read_example(22, "test")).unwrap().secrets,
+                2000
+            ),
+            24
+        );
+    }
+
+    #[test]
+    fn no_buyers() {
+        assert!(Day22::parse("\n").is_err());
+        // Too few rounds to see four price changes
+        assert_eq!(part_2(&[1, 2, 3], 3), 0);
+    }
+
+    #[test]
+    fn many_buyers() {
+        // Every buyer sells for the same changes, more bananas than a `u16` holds
+        let buyers = vec![1; 8_000];
+        let best = part_2(&[1], 50);
+        assert!(best * 8_000 > u16::MAX as u64);
+        assert_eq!(part_2(&buyers, 50), best * 8_000);
+    }
 }
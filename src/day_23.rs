@@ -1,97 +1,81 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs::read_to_string,
-    sync::Arc,
+use itertools::Itertools;
+
+use crate::{
+    generate::{Generated, Generator},
+    graph::Graph,
+    parse::Source,
+    Answer, Result, Solution,
 };
 
-use itertools::Itertools;
-use regex::Regex;
+pub struct Day23;
 
-struct Graph<T> {
-    nodes: HashSet<Arc<T>>,
-    edges: HashMap<Arc<T>, HashSet<Arc<T>>>,
-}
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+
+    type Input = Graph<String>;
 
-impl<T: std::cmp::Eq + std::hash::Hash + ToOwned> Graph<T> {
-    fn add_edge(&mut self, node_a: T, node_b: T) {
-        let a = Arc::new(node_a);
-        let b = Arc::new(node_b);
-        self._add_to_edges(&a, &b);
-        self._add_to_edges(&b, &a);
-        self.nodes.insert(a);
-        self.nodes.insert(b);
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_input(input)
     }
 
-    fn _add_to_edges(&mut self, node_a: &Arc<T>, node_b: &Arc<T>) {
-        self.edges
-            .entry(node_a.clone())
-            .or_insert(HashSet::new())
-            .insert(node_b.clone());
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
     }
 
-    fn find_maximal_cliques(&self) -> Vec<HashSet<Arc<T>>> {
-        self._bron_kerbosch_1(HashSet::new(), self.nodes.clone(), HashSet::new())
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
     }
 
-    fn _bron_kerbosch_1(
-        &self,
-        r: HashSet<Arc<T>>,
-        mut p: HashSet<Arc<T>>,
-        mut x: HashSet<Arc<T>>,
-    ) -> Vec<HashSet<Arc<T>>> {
-        if p.is_empty() && x.is_empty() {
-            return vec![r];
+    fn generate(generator: &mut Generator) -> Generated {
+        let mut computers = Vec::new();
+        while computers.len() < 4 * generator.size() {
+            let name = generator.word("abcdefghijklmnopqrstuvwxyz", 2);
+            if !computers.contains(&name) {
+                computers.push(name);
+            }
+        }
+        let mut links = Vec::new();
+        for (a, b) in computers.iter().tuple_combinations() {
+            if generator.chance(0.1) {
+                links.push((a, b));
+            }
         }
-        let mut cliques = Vec::new();
-        for v in p.clone() {
-            let neighbours = &self.edges[&v];
-            cliques.extend(self._bron_kerbosch_1(
-                r.union(&HashSet::from([v.clone()])).cloned().collect(),
-                p.clone().intersection(neighbours).cloned().collect(),
-                x.intersection(neighbours).cloned().collect(),
-            ));
-            p.remove(&v);
-            x.insert(v.clone());
+        // A party bigger than what chance links up, for the second part
+        let mut party = computers.clone();
+        generator.shuffle(&mut party);
+        for (a, b) in party[..generator.size() / 2 + 3]
+            .iter()
+            .tuple_combinations()
+        {
+            links.push((a, b));
         }
-        cliques
+        generator.shuffle(&mut links);
+        let lines = links.iter().unique().map(|(a, b)| format!("{a}-{b}\n"));
+        Generated::new(lines.collect())
     }
 }
 
-fn main() {
-    let test = read_input("./input/day_23.test.txt");
-    let input = read_input("./input/day_23.txt");
-
-    println!("First part test answer: {} == 7", part_1(&test));
-    println!("First part answer: {}", part_1(&input));
-
-    println!("Second part test answer: {}", part_2(&test));
-    println!("Second part answer: {}", part_2(&input));
-}
-
 fn part_1(graph: &Graph<String>) -> usize {
     graph
-        .nodes
-        .iter()
-        .filter(|node| node.starts_with('t'))
-        .map(|node| {
-            let mut triangles: Vec<Vec<&String>> = Vec::new();
-            let connections = Vec::from_iter(graph.edges[node].iter());
+        .nodes()
+        .filter(|&node| graph.node(node).starts_with('t'))
+        .flat_map(|node| {
+            let connections: Vec<_> = graph.neighbors(node).collect();
             let n = connections.len();
+            let mut triangles = Vec::new();
             for i in 0..n {
                 for j in i..n {
-                    let b = connections[i];
-                    let c = connections[j];
-                    if graph.edges[b].contains(c) {
-                        triangles.push(vec![node, b, c]);
+                    let (b, c) = (connections[i], connections[j]);
+                    if graph.has_edge(b, c) {
+                        triangles.push([node, b, c]);
                     }
                 }
             }
             triangles
         })
-        .flatten()
-        .map(|mut v| {
-            v.sort();
-            v
+        .map(|mut triangle| {
+            triangle.sort();
+            triangle
         })
         .unique()
         .count()
@@ -99,36 +83,42 @@ fn part_1(graph: &Graph<String>) -> usize {
 
 fn part_2(graph: &Graph<String>) -> String {
     graph
-        .find_maximal_cliques()
+        .maximal_cliques()
         .iter()
-        .sorted_by(|a, b| Ord::cmp(&a.len(), &b.len()))
-        .rev()
-        .next()
+        .max_by_key(|clique| clique.len())
         .unwrap()
         .iter()
+        .map(|&node| graph.node(node))
         .sorted()
         .join(",")
 }
 
-fn read_input(file_path: &str) -> Graph<String> {
-    let edge_pattern = Regex::new(r"(\w+)-(\w+)").unwrap();
-    read_to_string(file_path)
-        .unwrap()
-        .trim()
-        .split("\n")
-        .filter_map(|line| edge_pattern.captures(line))
-        .map(|captured| {
-            let (_, [node_a, node_b]) = captured.extract();
-            (node_a, node_b)
+fn read_input(input: &str) -> Result<Graph<String>> {
+    let source = Source::new(input);
+    source
+        .lines()
+        .try_fold(Graph::undirected(), |mut graph, line| {
+            let (node_a, node_b) = source.split_once(line, "-")?;
+            graph.add_edge(node_a.to_owned(), node_b.to_owned());
+            Ok(graph)
         })
-        .fold(
-            Graph {
-                nodes: HashSet::new(),
-                edges: HashMap::new(),
-            },
-            |mut graph, edge| {
-                graph.add_edge(edge.0.to_owned(), edge.1.to_owned());
-                graph
-            },
-        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(part_1(&Day23::parse(&read_example(23, "test")).unwrap()), 7);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(
+            part_2(&Day23::parse(&read_example(23, "test")).unwrap()),
+            "co,de,ka,ta"
+        );
+    }
 }
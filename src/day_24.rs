@@ -1,20 +1,24 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    fs::read_to_string,
-};
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
-use regex::Regex;
+
+use crate::{
+    generate::{Generated, Generator},
+    graph::Graph,
+    params::{Param, Params},
+    parse::Source,
+    Answer, Error, Result, Solution,
+};
 
 #[derive(PartialEq, Eq, Hash)]
-enum Operation {
+pub enum Operation {
     Xor,
     Or,
     And,
 }
 
 #[derive(PartialEq, Eq, Hash)]
-struct LogicGate {
+pub struct LogicGate {
     input_a: String,
     input_b: String,
     output: String,
@@ -23,95 +27,169 @@ struct LogicGate {
 
 impl LogicGate {
     fn is_input_node(&self) -> bool {
-        self.input_a.starts_with(&['x', 'y']) && self.input_b.starts_with(&['x', 'y'])
+        self.input_a.starts_with(['x', 'y']) && self.input_b.starts_with(['x', 'y'])
     }
 
     fn is_terminal_node(&self) -> bool {
         self.output.starts_with('z')
     }
 
-    fn is_next_of(&self, other: &LogicGate) -> bool {
-        self.input_a == other.output || self.input_b == other.output
-    }
-
     fn is_bit(&self, bit: u32) -> bool {
         let as_string = bit.to_string();
         self.input_a.ends_with(&as_string) && self.input_b.ends_with(&as_string)
     }
 }
 
-fn main() {
-    let test_1 = read_input("./input/day_24.test.1.txt");
-    let test_2 = read_input("./input/day_24.test.2.txt");
-    let input = read_input("./input/day_24.txt");
-
-    println!(
-        "First part test 1 answer: {} == 4",
-        part_1(&test_1.0, &test_1.1)
-    );
-    println!(
-        "First part test 2 answer: {} == 2024",
-        part_1(&test_2.0, &test_2.1)
-    );
-    println!("First part answer: {}", part_1(&input.0, &input.1));
-    println!("Second part answer: {}", part_2(&input.1));
+/// The initial wire values and the gates of the adder, with the wire that
+/// carries its last bit out
+pub struct Circuit {
+    wires: HashMap<String, bool>,
+    gates: Vec<LogicGate>,
+    carry_out: String,
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+
+    type Input = Circuit;
+
+    const PARAMS: &'static [Param] = &[Param::new(
+        "carry_out",
+        "z45",
+        "output wire of the adder's final carry",
+    )];
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::defaults(Self::PARAMS))
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        let (wires, gates) = read_input(input)?;
+        let carry_out: String = params.get("carry_out")?;
+        if !carry_out.starts_with('z') || !gates.iter().any(|gate| gate.output == carry_out) {
+            return Err(Error::BadParam {
+                name: "carry_out".to_owned(),
+                reason: format!("`{carry_out}` is not a `z` wire set by a gate"),
+            });
+        }
+        Ok(Circuit {
+            wires,
+            gates,
+            carry_out,
+        })
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(&input.wires, &input.gates).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(&input.gates, &input.carry_out).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        // A ripple-carry adder like the puzzle's, with four pairs of outputs
+        // swapped within single bits once it is wide enough
+        let bits = generator.size().clamp(2, 45);
+        let mut names = HashSet::new();
+        let mut wire = |generator: &mut Generator| loop {
+            let name = generator.word("abcdefghijklmnopqrstuvw", 3);
+            if names.insert(name.clone()) {
+                return name;
+            }
+        };
+
+        let mut gates = vec![
+            ("x00".to_owned(), "XOR", "y00".to_owned(), "z00".to_owned()),
+            ("x00".to_owned(), "AND", "y00".to_owned(), wire(generator)),
+        ];
+        let mut carry = gates[1].3.clone();
+        let mut swappable = Vec::new();
+        for bit in 1..bits {
+            let (x, y, z) = (
+                format!("x{bit:02}"),
+                format!("y{bit:02}"),
+                format!("z{bit:02}"),
+            );
+            let (sum, both, through) = (wire(generator), wire(generator), wire(generator));
+            let carry_out = match bit + 1 == bits {
+                true => format!("z{bits:02}"),
+                false => wire(generator),
+            };
+            let first = gates.len();
+            gates.push((x.clone(), "XOR", y.clone(), sum.clone()));
+            gates.push((x, "AND", y, both.clone()));
+            gates.push((sum.clone(), "XOR", carry.clone(), z));
+            gates.push((sum, "AND", carry, through.clone()));
+            gates.push((both, "OR", through, carry_out.clone()));
+            if bit + 1 < bits {
+                // The sum with one of the carry gates, or the two halves
+                swappable.push(match generator.number(0..4) {
+                    0 => (first + 2, first + 4),
+                    1 => (first + 2, first + 3),
+                    2 => (first + 2, first + 1),
+                    _ => (first, first + 1),
+                });
+            }
+            carry = carry_out;
+        }
+        if swappable.len() >= 8 {
+            generator.shuffle(&mut swappable);
+            for &(a, b) in &swappable[..4] {
+                let output = gates[a].3.clone();
+                gates[a].3 = std::mem::replace(&mut gates[b].3, output);
+            }
+        }
+        generator.shuffle(&mut gates);
+
+        let mut input = String::new();
+        for prefix in ['x', 'y'] {
+            for bit in 0..bits {
+                input.push_str(&format!("{prefix}{bit:02}: {}\n", generator.number(0..=1)));
+            }
+        }
+        input.push('\n');
+        for (a, operation, b, output) in gates {
+            input.push_str(&format!("{a} {operation} {b} -> {output}\n"));
+        }
+        Generated::new(input).with("carry_out", format!("z{bits:02}"))
+    }
+}
+
+/// Wires pointing to the wires of the gates they feed
+fn wiring(gates: &[LogicGate]) -> Graph<&str> {
+    gates.iter().fold(Graph::directed(), |mut graph, gate| {
+        graph.add_edge(gate.input_a.as_str(), gate.output.as_str());
+        graph.add_edge(gate.input_b.as_str(), gate.output.as_str());
+        graph
+    })
 }
 
+/// Values of the `z` wires, or `None` when the gates loop on themselves or
+/// wait on a wire nothing sets
 fn evaluate_circuit(
     initial_values: &HashMap<String, bool>,
-    gates: &Vec<LogicGate>,
+    gates: &[LogicGate],
 ) -> Option<HashMap<String, bool>> {
-    // Uses deferred to wait for other calculations, instead of
-    // re-queueing the gate over and over.
-    // This way we avoid infinite loops due to poorly connected
-    // circuit graph. This was useful for part 2 until I had to
-    // rethink what I trying to do with my brute-force.
+    let wiring = wiring(gates);
+    let by_output: HashMap<&str, &LogicGate> =
+        gates.iter().map(|g| (g.output.as_str(), g)).collect();
     let mut memory = initial_values.clone();
-    let mut queue = gates.into_iter().collect::<VecDeque<_>>();
-    let mut deferred: HashMap<&String, HashSet<&LogicGate>> =
-        gates.iter().fold(HashMap::new(), |mut map, value| {
-            map.insert(&value.output, HashSet::new());
-            map
-        });
 
-    while let Some(g) = queue.pop_front() {
-        let value_a = memory.get(&g.input_a);
-        let value_b = memory.get(&g.input_b);
-        if value_a.is_none() {
-            deferred.entry(&g.input_a).and_modify(|v| {
-                v.insert(g);
-            });
-            continue;
-        }
-        if value_b.is_none() {
-            deferred.entry(&g.input_b).and_modify(|v| {
-                v.insert(g);
-            });
+    for wire in wiring.topological_sort()? {
+        let Some(g) = by_output.get(wiring.node(wire)) else {
             continue;
-        }
-
-        let val_a = value_a.unwrap();
-        let val_b = value_b.unwrap();
+        };
+        let val_a = *memory.get(&g.input_a)?;
+        let val_b = *memory.get(&g.input_b)?;
         let output_value = match g.operation {
             Operation::And => val_a & val_b,
             Operation::Or => val_a | val_b,
             Operation::Xor => val_a ^ val_b,
         };
-
         memory.insert(g.output.clone(), output_value);
-
-        // Awake deferred calculations
-        let waiting_on = deferred.get_mut(&g.output).unwrap();
-        if !waiting_on.is_empty() {
-            queue.extend(waiting_on.iter());
-            waiting_on.clear();
-        }
-    }
-
-    // If there are any still any deferred calculations, then the
-    // circuit graph is bad.
-    if deferred.iter().any(|(_, waiting)| !waiting.is_empty()) {
-        return None;
     }
 
     Some(
@@ -122,21 +200,29 @@ fn evaluate_circuit(
     )
 }
 
-fn part_1(initial_values: &HashMap<String, bool>, instructions: &Vec<LogicGate>) -> u64 {
-    memory_to_number(
-        &evaluate_circuit(initial_values, instructions).unwrap(),
-        'z',
-    )
+/// The number on the `z` wires, if it fits
+fn part_1(initial_values: &HashMap<String, bool>, instructions: &[LogicGate]) -> Option<u64> {
+    memory_to_number(&evaluate_circuit(initial_values, instructions)?, 'z')
 }
 
-fn find_bad_gates(gates: &Vec<LogicGate>) -> Vec<&String> {
+fn find_bad_gates<'a>(gates: &'a [LogicGate], carry_out: &str) -> Vec<&'a String> {
     // All credit to my saviours: @ropewalker and @wilkotom at Github
     // https://github.com/ropewalker/advent_of_code_2024/blob/master/src/day24.rs
     // https://github.com/wilkotom/AdventOfCode/blob/main/rust/2024/day24/src/main.rs
+    let wiring = wiring(gates);
+    let by_output: HashMap<&str, &LogicGate> =
+        gates.iter().map(|g| (g.output.as_str(), g)).collect();
+    // Whether the output of `g` feeds a gate doing `operation`
+    let feeds = |g: &LogicGate, operation: Operation| {
+        let wire = wiring.id(&g.output.as_str()).unwrap();
+        wiring
+            .neighbors(wire)
+            .any(|next| by_output[wiring.node(next)].operation == operation)
+    };
     let mut to_replace: Vec<&String> = vec![];
 
     for g in gates {
-        if g.is_terminal_node() && g.operation != Operation::Xor && g.output != "z45" {
+        if g.is_terminal_node() && g.operation != Operation::Xor && g.output != carry_out {
             to_replace.push(&g.output);
         }
         if !(g.is_terminal_node() || g.is_input_node()) && g.operation == Operation::Xor {
@@ -145,18 +231,11 @@ fn find_bad_gates(gates: &Vec<LogicGate>) -> Vec<&String> {
         if g.operation == Operation::Xor
             && g.is_input_node()
             && g.output != "z00"
-            && !gates
-                .iter()
-                .any(|next| next.operation == Operation::Xor && next.is_next_of(g))
+            && !feeds(g, Operation::Xor)
         {
             to_replace.push(&g.output);
         }
-        if g.operation == Operation::And
-            && !g.is_bit(0)
-            && !gates
-                .iter()
-                .any(|next| next.operation == Operation::Or && next.is_next_of(g))
-        {
+        if g.operation == Operation::And && !g.is_bit(0) && !feeds(g, Operation::Or) {
             to_replace.push(&g.output);
         }
     }
@@ -164,13 +243,13 @@ fn find_bad_gates(gates: &Vec<LogicGate>) -> Vec<&String> {
     to_replace.into_iter().unique().sorted().collect()
 }
 
-fn part_2(gates: &Vec<LogicGate>) -> String {
-    find_bad_gates(gates).into_iter().join(",")
+fn part_2(gates: &[LogicGate], carry_out: &str) -> String {
+    find_bad_gates(gates, carry_out).into_iter().join(",")
 }
 
 fn memory_to_binary_string(memory: &HashMap<String, bool>, prefix: char) -> String {
     memory
-        .into_iter()
+        .iter()
         .filter(|(key, _)| key.starts_with(prefix))
         .sorted()
         .rev()
@@ -181,43 +260,124 @@ fn memory_to_binary_string(memory: &HashMap<String, bool>, prefix: char) -> Stri
         .join("")
 }
 
-fn memory_to_number(memory: &HashMap<String, bool>, prefix: char) -> u64 {
-    u64::from_str_radix(&memory_to_binary_string(memory, prefix), 2).unwrap()
+fn memory_to_number(memory: &HashMap<String, bool>, prefix: char) -> Option<u64> {
+    u64::from_str_radix(&memory_to_binary_string(memory, prefix), 2).ok()
 }
 
-fn read_input(file_path: &str) -> (HashMap<String, bool>, Vec<LogicGate>) {
-    let break_pattern = Regex::new(r"(\r?\n){2,}").unwrap();
-    let string = read_to_string(file_path).unwrap().trim().to_owned();
-    let parts: Vec<&str> = break_pattern.split(&string).collect();
-
-    let variable_pattern = Regex::new(r"([\w\d]+): (\d)").unwrap();
-    let gate_pattern = Regex::new(r"([\w\d]+) (\w+) ([\w\d]+) -> ([\w\d]+)").unwrap();
-
-    (
-        variable_pattern
-            .captures_iter(parts[0])
-            .fold(HashMap::new(), |mut map, entry| {
-                let (_, [id, value]) = entry.extract();
-                let id_owned = id.to_string();
-                map.insert(id_owned, value.parse::<u8>().unwrap() != 0);
-                map
-            }),
-        gate_pattern
-            .captures_iter(parts[1])
-            .fold(Vec::new(), |mut vec, entry| {
-                let (_, [id_a, operation, id_b, id_out]) = entry.extract();
-                vec.push(LogicGate {
-                    input_a: id_a.to_owned(),
-                    input_b: id_b.to_owned(),
-                    output: id_out.to_owned(),
-                    operation: match operation {
-                        "XOR" => Operation::Xor,
-                        "AND" => Operation::And,
-                        "OR" => Operation::Or,
-                        e => panic!("Unknown operation {e}"),
-                    },
-                });
-                vec
-            }),
-    )
+fn read_input(input: &str) -> Result<(HashMap<String, bool>, Vec<LogicGate>)> {
+    let [wires, gates] = Source::new(input).split_sections(["wires", "gates"])?;
+
+    let values = wires.lines().try_fold(HashMap::new(), |mut map, line| {
+        let (id, value) = wires.split_once(line, ": ")?;
+        map.insert(id.to_owned(), wires.number::<u8>(value)? != 0);
+        Ok(map)
+    })?;
+
+    // The wires each gate reads, and the one it sets, as written
+    let mut wired: Vec<([&str; 2], &str)> = Vec::new();
+    let mut logic = Vec::new();
+    for line in gates.lines() {
+        let (inputs, output) = gates.split_once(line, " -> ")?;
+        let [input_a, operation, input_b]: [String; 3] = gates.fields("{} {} {}", inputs)?;
+        let operation = match operation.as_str() {
+            "XOR" => Operation::Xor,
+            "AND" => Operation::And,
+            "OR" => Operation::Or,
+            _ => return Err(gates.unexpected(inputs, "AND, OR or XOR")),
+        };
+        if values.contains_key(output) || wired.iter().any(|&(_, other)| other == output) {
+            return Err(gates.unexpected(output, "a wire that nothing else sets"));
+        }
+        let names: Vec<&str> = inputs.split_whitespace().collect();
+        wired.push(([names[0], names[names.len() - 1]], output));
+        logic.push(LogicGate {
+            input_a,
+            input_b,
+            output: output.to_owned(),
+            operation,
+        });
+    }
+
+    for &([a, b], _) in &wired {
+        for input in [a, b] {
+            if !values.contains_key(input) && !wired.iter().any(|&(_, output)| output == input) {
+                return Err(gates.unexpected(input, "a wire that is given or set by a gate"));
+            }
+        }
+    }
+    // A gate that can reach its own output again never settles
+    let wiring = wiring(&logic);
+    let looping = wired.iter().find(|&&(_, output)| {
+        let Some(start) = wiring.id(&output) else {
+            return false;
+        };
+        let mut seen = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(wire) = stack.pop() {
+            for next in wiring.neighbors(wire) {
+                if next == start {
+                    return true;
+                }
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    });
+    if let Some(&(_, output)) = looping {
+        return Err(gates.unexpected(output, "a wire that does not feed back into itself"));
+    }
+    Ok((values, logic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_examples() {
+        let (wires, gates) = read_input(&read_example(24, "test.1")).unwrap();
+        assert_eq!(part_1(&wires, &gates), Some(4));
+        let (wires, gates) = read_input(&read_example(24, "test.2")).unwrap();
+        assert_eq!(part_1(&wires, &gates), Some(2024));
+    }
+
+    #[test]
+    fn bad_circuits() {
+        for (gates, error) in [
+            (
+                "x00 AND y00 -> z00\nx00 OR z00 -> z00",
+                "line 5, column 15 (in gates): unexpected `z00`, expected a wire that nothing else sets",
+            ),
+            (
+                "x00 AND abc -> z00",
+                "line 4, column 9 (in gates): unexpected `abc`, expected a wire that is given or set by a gate",
+            ),
+            (
+                "x00 AND abc -> z00\nz00 XOR y00 -> abc",
+                "line 4, column 16 (in gates): unexpected `z00`, expected a wire that does not feed back into itself",
+            ),
+        ] {
+            let input = format!("x00: 1\ny00: 0\n\n{gates}\n");
+            let Err(parsed) = read_input(&input) else {
+                panic!("{gates} parsed");
+            };
+            assert_eq!(parsed.to_string(), error);
+        }
+    }
+
+    #[test]
+    fn carry_out_is_a_z_wire() {
+        let mut params = Params::defaults(Day24::PARAMS);
+        params.set("carry_out", "z99");
+        let Err(refused) = Day24::parse_with(&read_example(24, "test.1"), &params) else {
+            panic!("a wire that no gate sets was accepted");
+        };
+        assert_eq!(
+            refused.to_string(),
+            "bad parameter `carry_out`: `z99` is not a `z` wire set by a gate"
+        );
+    }
 }
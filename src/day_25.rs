@@ -1,25 +1,68 @@
-use std::fs::read_to_string;
+use itertools::Itertools;
 
-use grid::Grid;
-use regex::Regex;
+use crate::{
+    generate::{Generated, Generator},
+    grid::Grid,
+    parse::Source,
+    Answer, Result, Solution,
+};
 
-fn main() {
-    let test = read_input("./input/day_25.test.txt");
-    let input = read_input("./input/day_25.txt");
+pub struct Day25;
 
-    println!("Test answer: {:?}", fit_keys_and_locks(&test));
-    println!("Answer: {:?}", fit_keys_and_locks(&input));
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+
+    type Input = Vec<Grid<char>>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_input(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        fit_keys_and_locks(input).into()
+    }
+
+    // The last day has no second puzzle
+    fn part_2(_input: &Self::Input) -> Answer {
+        Answer::None
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let mut schematics = Vec::new();
+        for _ in 0..2 * generator.size() {
+            let heights: Vec<usize> = (0..5).map(|_| generator.number(0..=5)).collect();
+            let is_lock = generator.chance(0.5);
+            let rows = (0..7).map(|row| {
+                heights
+                    .iter()
+                    .map(|&height| {
+                        // Locks hang from the top row, keys stand on the bottom one
+                        let filled = match is_lock {
+                            true => row <= height,
+                            false => row >= 6 - height,
+                        };
+                        match filled {
+                            true => '#',
+                            false => '.',
+                        }
+                    })
+                    .collect::<String>()
+            });
+            schematics.push(rows.collect_vec().join("\n") + "\n");
+        }
+        Generated::new(schematics.join("\n"))
+    }
 }
 
-fn fit_keys_and_locks(input: &Vec<Grid<char>>) -> usize {
+fn fit_keys_and_locks(input: &[Grid<char>]) -> usize {
     let mut locks = Vec::new();
     let mut keys = Vec::new();
 
     for object in input {
-        let is_a_lock = object.iter_row(0).all(|&c| c == '#');
+        let is_a_lock = object.row(0).iter().all(|&c| c == '#');
 
         let as_height_map: Vec<usize> = object
-            .iter_cols()
+            .columns()
             .map(|v| v.filter(|&&c| c == '#').count() - 1)
             .collect();
 
@@ -33,23 +76,33 @@ fn fit_keys_and_locks(input: &Vec<Grid<char>>) -> usize {
     let count_locks = locks.len();
     let count_keys = keys.len();
     (0..count_locks)
-        .map(|i| (0..count_keys).map(move |j| (i, j)))
-        .flatten()
+        .flat_map(|i| (0..count_keys).map(move |j| (i, j)))
         .filter(|(i, j)| (0..5).all(|k| locks[*i][k] + keys[*j][k] < 6))
         .count()
 }
 
-fn read_input(file_path: &str) -> Vec<Grid<char>> {
-    let string = read_to_string(file_path).unwrap().trim().to_owned();
-    let break_pattern = Regex::new(r"(\r?\n){2,}").unwrap();
-
-    break_pattern
-        .split(&string)
+fn read_input(input: &str) -> Result<Vec<Grid<char>>> {
+    Source::new(input)
+        .sections()
         .map(|pattern| {
-            Grid::from_vec(
-                Vec::from_iter(pattern.replace("\r", "").replace("\n", "").chars()),
-                5,
-            )
+            if let Some(row) = pattern.lines().find(|row| row.chars().count() != 5) {
+                return Err(pattern.unexpected(row, "a row of 5 tiles"));
+            }
+            pattern.grid(pattern.text())
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            fit_keys_and_locks(&Day25::parse(&read_example(25, "test")).unwrap()),
+            3
+        );
+    }
+}
@@ -1,26 +1,70 @@
-mod utilities;
-use utilities::read_lines;
-
+use log::trace;
 use regex::Regex;
 
-fn main() {
-    let buffer: String;
-    match read_lines("./input/day_3.txt") {
-        Ok(lines) => {
-            buffer = lines.flatten().reduce(|acc, e| acc + &e).unwrap();
-        }
-        Err(e) => {
-            panic!("Unable to read file! {e}");
+use crate::{
+    generate::{Generated, Generator},
+    Answer, Result, Solution,
+};
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(read_input(input))
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let mut input = String::new();
+        for _ in 0..generator.size() {
+            for _ in 0..40 {
+                let (a, b): (u32, u32) = (generator.number(1..1000), generator.number(1..1000));
+                let token = match generator.number(0..10) {
+                    0..=2 => format!("mul({a},{b})"),
+                    3 => "do()".to_owned(),
+                    4 => "don't()".to_owned(),
+                    // Instructions that are almost right, and do nothing
+                    5 => format!("mul({a}, {b})"),
+                    6 => format!("mul[{a},{b}]"),
+                    7 => format!("mul({a},{b}"),
+                    _ => {
+                        let length = generator.number(1..=4);
+                        generator.word("!@#$%^&*()[]{}<>?,:;'+-_ ", length)
+                    }
+                };
+                input.push_str(&token);
+            }
+            input.push('\n');
         }
+        Generated::new(input)
     }
+}
+
+fn mul_pattern() -> Regex {
+    Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap()
+}
+
+fn part_1(buffer: &str) -> i32 {
     let mut products: Vec<i32> = Vec::new();
-    let mul_pattern = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
-    for (_, [term_a, term_b]) in mul_pattern.captures_iter(&buffer).map(|c| c.extract()) {
+    for (_, [term_a, term_b]) in mul_pattern().captures_iter(buffer).map(|c| c.extract()) {
         products.push(term_a.parse::<i32>().unwrap() * term_b.parse::<i32>().unwrap());
     }
-    let part_1_sum: i32 = products.iter().sum();
-    println!("First part answer: {part_1_sum}");
+    products.iter().sum()
+}
 
+fn part_2(buffer: &str) -> i32 {
+    let mul_pattern = mul_pattern();
     let program_pattern = Regex::new(&format!(
         "({}|{}|{})",
         r"do\(\)",
@@ -28,11 +72,11 @@ fn main() {
         mul_pattern.as_str()
     ))
     .unwrap();
-    products.clear();
+    let mut products: Vec<i32> = Vec::new();
     let mut mul_enabled = true;
-    for token in program_pattern.find_iter(&buffer) {
+    for token in program_pattern.find_iter(buffer) {
         let instruction = token.as_str();
-        println!("{}", instruction);
+        trace!("{instruction}");
         match instruction {
             "do()" => {
                 mul_enabled = true;
@@ -42,18 +86,19 @@ fn main() {
             }
             _ => {
                 if mul_enabled {
-                    match mul_pattern.captures(instruction).map(|c| c.extract()) {
-                        Some((_, [term_a, term_b])) => {
-                            products.push(
-                                term_a.parse::<i32>().unwrap() * term_b.parse::<i32>().unwrap(),
-                            );
-                        }
-                        None => {}
+                    if let Some((_, [term_a, term_b])) =
+                        mul_pattern.captures(instruction).map(|c| c.extract())
+                    {
+                        products
+                            .push(term_a.parse::<i32>().unwrap() * term_b.parse::<i32>().unwrap());
                     }
                 }
             }
         }
     }
-    let part_2_sum: i32 = products.iter().sum();
-    println!("First part answer: {part_2_sum}");
+    products.iter().sum()
+}
+
+fn read_input(input: &str) -> String {
+    input.lines().collect()
 }
@@ -1,130 +1,144 @@
 use itertools::Itertools;
-use std::{collections::HashMap, fs::read_to_string};
 
-use regex::Regex;
-
-fn main() {
-    let (test_rules, test_chains) = parse_input("./input/day_5.test.txt");
-
-    println!(
-        "First part test answer: {} == 143",
-        part_1(&test_rules, &test_chains)
-    );
-
-    let (rules, chains) = parse_input("./input/day_5.txt");
-
-    println!("First part answer: {}", part_1(&rules, &chains));
-
-    println!(
-        "Second part test answer: {} == 123",
-        part_2(&test_rules, &test_chains)
-    );
+use crate::{
+    generate::{Generated, Generator},
+    graph::Graph,
+    parse::Source,
+    Answer, Result, Solution,
+};
+
+/// Pages pointing to the pages that must be printed after them
+type OrderRules = Graph<i32>;
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = (OrderRules, Vec<Vec<i32>>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(&input.0, &input.1).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(&input.0, &input.1).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        // Every pair of pages gets a rule following one order, so the pages
+        // of an update can always be sorted
+        let mut order: Vec<u32> = (10..100).collect();
+        generator.shuffle(&mut order);
+        order.truncate(generator.size() + 5);
+        let mut rules: Vec<String> = order
+            .iter()
+            .tuple_combinations()
+            .map(|(before, after)| format!("{before}|{after}"))
+            .collect();
+        generator.shuffle(&mut rules);
+
+        let mut updates = Vec::new();
+        for _ in 0..generator.size() {
+            let length = generator.number(1..=order.len().min(9).div_ceil(2)) * 2 - 1;
+            let mut pages = order.clone();
+            generator.shuffle(&mut pages);
+            pages.truncate(length);
+            if generator.chance(0.5) {
+                pages.sort_by_key(|page| order.iter().position(|other| other == page));
+            }
+            updates.push(pages.iter().join(","));
+        }
+        Generated::new(format!("{}\n\n{}\n", rules.join("\n"), updates.join("\n")))
+    }
+}
 
-    println!("Second part answer: {}", part_2(&rules, &chains))
+/// Whether no rule asks for a page to come before one printed earlier
+fn is_ordered(order_rules: &OrderRules, chain: &[i32]) -> bool {
+    let pages: Vec<_> = chain.iter().map(|page| order_rules.id(page)).collect();
+    pages.iter().enumerate().all(|(index, &page)| {
+        pages[index + 1..].iter().all(|&later| match (page, later) {
+            (Some(page), Some(later)) => !order_rules.has_edge(later, page),
+            _ => true,
+        })
+    })
 }
 
-fn get_pos<T>(a: &Vec<T>, e: T) -> usize
-where
-    T: std::cmp::PartialEq + Copy,
-{
-    return a.iter().position(|&y| y == e).unwrap();
+fn part_1(order_rules: &OrderRules, chains: &[Vec<i32>]) -> i32 {
+    chains
+        .iter()
+        .filter(|chain| is_ordered(order_rules, chain))
+        .map(|chain| chain[chain.len() / 2])
+        .sum()
 }
 
-fn part_1(order_rules: &HashMap<i32, Vec<i32>>, chains: &Vec<Vec<i32>>) -> i32 {
-    let valid_chains: Vec<&Vec<i32>> = chains
+/// The middle pages of the corrected updates, unless the rules between the
+/// pages of one go round in a circle
+fn part_2(order_rules: &OrderRules, chains: &[Vec<i32>]) -> Option<i32> {
+    chains
         .iter()
-        .filter(|chain| {
-            chain.iter().enumerate().all(|(index, number)| {
-                chain
-                    .iter()
-                    .filter(|&x| match order_rules.get(number) {
-                        Some(rules) => rules.contains(x),
-                        None => false,
-                    })
-                    .all(|&x| index < get_pos(chain, x))
-            })
+        .filter(|chain| !is_ordered(order_rules, chain))
+        .map(|chain| {
+            let rules = order_rules.subgraph(chain);
+            let corrected = rules.topological_sort()?;
+            Some(*rules.node(corrected[corrected.len() / 2]))
         })
-        .collect();
-
-    return valid_chains.iter().map(|v| v[v.len() / 2]).sum();
+        .sum()
 }
 
-fn part_2(order_rules: &HashMap<i32, Vec<i32>>, chains: &Vec<Vec<i32>>) -> i32 {
-    let invalid_chains: Vec<&Vec<i32>> = chains
-    .iter()
-    .filter(|chain| {
-        chain.iter().enumerate().any(|(index, number)| {
-            chain
-                .iter()
-                .filter(|&x| match order_rules.get(number) {
-                    Some(rules) => rules.contains(x),
-                    None => false,
-                })
-                .any(|&x| !(index < get_pos(chain, x)))
-        })
-    })
-    .collect();
-
-    let corrected: Vec<Vec<&i32>> = invalid_chains.iter().map(|&chain| {
-        let mut new_chain: Vec<&i32> = Vec::new();
-        new_chain.push(&chain[0]);
-        for l in &chain[1..] {
-            let mut inserted = false;
-            for i in (0..new_chain.len()).rev() {
-                match order_rules.get(new_chain[i]) {
-                    Some(rule) => if rule.contains(l) {
-                        new_chain.insert(i + 1, l);
-                        inserted = true;
-                        break;
-                    }
-                    None => continue
-                }
+fn parse_input(buffer: &str) -> Result<(OrderRules, Vec<Vec<i32>>)> {
+    let [rules, updates] =
+        Source::new(buffer).split_sections(["ordering rules", "page updates"])?;
+    let mut order_rules = Graph::directed();
+    for line in rules.lines() {
+        let [before, after] = rules.fields("{}|{}", line)?;
+        order_rules.add_edge(before, after);
+    }
+    let chains = updates
+        .lines()
+        .map(|line| {
+            let chain: Vec<i32> = updates.numbers(line, &[','])?;
+            // The rules as a whole loop, but never between the pages of one update
+            match order_rules.subgraph(&chain).has_cycle() {
+                true => Err(updates.unexpected(line, "pages that the rules put in one order")),
+                false => Ok(chain),
             }
-            if !inserted {
-                new_chain.insert(0, l)
-            }
-        }
-        return new_chain;
-    }).collect();
-
-    return corrected.iter().map(|v| v[v.len() / 2]).sum();
+        })
+        .collect::<Result<_>>()?;
+    Ok((order_rules, chains))
 }
 
-fn parse_input(input_path: &str) -> (HashMap<i32, Vec<i32>>, Vec<Vec<i32>>) {
-    let buffer = read_to_string(input_path).unwrap();
-
-    let order_rule_pattern = Regex::new(r"(\d+)\|(\d+)").unwrap();
-    let chain_rule_pattern = Regex::new(r"(\d+,)+\d+").unwrap();
-
-    let order_rules: Vec<(i32, i32)> = order_rule_pattern
-        .find_iter(&buffer)
-        .map(|m| {
-            m.as_str()
-                .split_once("|")
-                .map(|(a, b)| (a.parse::<i32>().unwrap(), b.parse::<i32>().unwrap()))
-                .unwrap()
-        })
-        .collect();
-    let order_map: HashMap<i32, Vec<i32>> =
-        HashMap::from_iter(order_rules.iter().map(|x| x.0).unique().map(|key| {
-            (
-                key,
-                order_rules
-                    .iter()
-                    .filter(|(a, _)| *a == key)
-                    .map(|(_, b)| *b)
-                    .collect(),
-            )
-        }));
-
-    let chains: Vec<Vec<i32>> = chain_rule_pattern
-        .find_iter(&buffer)
-        .map(|m| {
-            m.as_str()
-                .split(",")
-                .map(|s| s.parse::<i32>().unwrap())
-                .collect()
-        })
-        .collect();
-    return (order_map, chains);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        let (rules, chains) = Day5::parse(&read_example(5, "test")).unwrap();
+        assert_eq!(part_1(&rules, &chains), 143);
+    }
+
+    #[test]
+    fn part_2_example() {
+        let (rules, chains) = Day5::parse(&read_example(5, "test")).unwrap();
+        assert_eq!(part_2(&rules, &chains), Some(123));
+    }
+
+    #[test]
+    fn looping_rules() {
+        let input = "1|2\n2|3\n3|1\n\n1,2\n3,2,1\n";
+        let Err(error) = Day5::parse(input) else {
+            panic!("rules in a circle parsed");
+        };
+        assert_eq!(
+            error.to_string(),
+            "line 6, column 1 (in page updates): unexpected `3,2,1`, \
+             expected pages that the rules put in one order"
+        );
+    }
 }
@@ -1,7 +1,17 @@
 use itertools::Itertools;
-use regex::Regex;
+use log::{debug, trace};
 use rayon::prelude::*;
-use std::{collections::HashSet, fs::read_to_string};
+use std::collections::HashSet;
+
+use crate::{
+    animation::Recorder,
+    generate::{Generated, Generator},
+    grid::Grid,
+    parse::Source,
+    point::{Direction, Point},
+    render::{Canvas, Color},
+    Answer, Part, Result, Solution,
+};
 
 pub enum WalkResult<T> {
     Stop(T),
@@ -13,85 +23,168 @@ pub enum PatrolResult<T> {
     Loop,
 }
 impl<T> PatrolResult<T> {
-    fn unwrap(self) -> T {
+    fn ok(self) -> Option<T> {
         match self {
-            PatrolResult::Ok(value) => value,
-            PatrolResult::Loop => panic!("Unhandled loop detected!"),
+            PatrolResult::Ok(value) => Some(value),
+            PatrolResult::Loop => None,
         }
     }
 }
 
-type Point = (i32, i32);
-type Direction = (i32, i32);
 type VisitedPoint = (Point, Direction);
 
-fn main() {
-    let test_map = read_map("./input/day_6.test.txt");
-    println!("First part test answer: {} == 41", part_1(&test_map));
+/// The map of the lab, and where the guard starts, facing north
+pub struct Lab {
+    map: Grid<char>,
+    guard: Point,
+}
 
-    let map = read_map("./input/day_6.txt");
-    println!("First part answer: {}", part_1(&map));
+pub struct Day6;
 
-    println!("Second part test answer: {} == 6", part_2(&test_map));
-    println!("Second part answer: {}", part_2(&map));
-}
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Lab;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let source = Source::new(input);
+        Ok(Lab {
+            map: source.grid(input)?,
+            guard: source.tile(input, '^', "guard")?,
+        })
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let side = generator.size() + 4;
+        // Maps where the guard walks in circles, or only turns on the spot
+        // when walled in, have no answer, so try again
+        loop {
+            let mut map = Grid::new(side, side, '.');
+            for point in map.positions().collect_vec() {
+                if generator.chance(0.12) {
+                    map[point] = '#';
+                }
+            }
+            let free = map.find_all(|&c| c == '.').collect_vec();
+            let start = *generator.pick(&free);
+            map[start] = '^';
+            if let PatrolResult::Ok(_) = patrol(&map, start) {
+                return Generated::new(map.to_string() + "\n");
+            }
+        }
+    }
+
+    fn render(input: &Self::Input, part: Part) -> Option<Canvas> {
+        let visited = patrol(&input.map, input.guard).ok()?;
+        let canvas = Canvas::new(input.map.clone());
+        Some(match part {
+            Part::One => canvas.mark("visited", Color::Yellow, 'X', visited),
+            Part::Two => canvas.highlight("visited", Color::Yellow, visited).mark(
+                "loop obstruction",
+                Color::Red,
+                'O',
+                loop_obstructions(input)?,
+            ),
+        })
+    }
 
-fn get_map(map: &Vec<String>, position: (i32, i32)) -> Option<char> {
-    let (x, y) = position;
-    match map.get(y as usize) {
-        Some(row) => match row.as_bytes().get(x as usize) {
-            Some(block) => Some(*block as char),
-            None => None,
-        },
-        None => None,
+    fn animate(input: &Self::Input, part: Part, recorder: &mut Recorder) -> bool {
+        if part == Part::Two {
+            return false;
+        }
+        // One step at a time, where `patrol` walks whole segments
+        let mut guard = input.guard;
+        let mut direction = Direction::North;
+        let mut visited = HashSet::from([guard]);
+        while !recorder.is_done() {
+            recorder.frame(|| {
+                Canvas::new(input.map.clone())
+                    .highlight("visited", Color::Yellow, visited.iter().copied())
+                    .mark("guard", Color::Red, direction.glyph().unwrap(), [guard])
+            });
+            let next = guard.step(direction);
+            match input.map.get(next) {
+                None => break,
+                Some('#') => direction = direction.turn_right(),
+                Some(_) => {
+                    guard = next;
+                    visited.insert(guard);
+                }
+            }
+        }
+        true
     }
 }
 
-fn part_1(map: &Vec<String>) -> u32 {
-    let start = starting_position(map);
-    patrol(map, start).unwrap().len() as u32
+/// Tiles the guard visits, unless the guard never leaves the lab
+fn part_1(lab: &Lab) -> Option<u32> {
+    Some(patrol(&lab.map, lab.guard).ok()?.len() as u32)
+}
+
+fn part_2(lab: &Lab) -> Option<u32> {
+    Some(loop_obstructions(lab)?.len() as u32)
 }
 
-fn part_2(map: &Vec<String>) -> u32 {
-    let start = starting_position(map);
+/// Tiles where a new obstruction would trap the guard in a loop, unless the
+/// guard is trapped already
+fn loop_obstructions(lab: &Lab) -> Option<Vec<Point>> {
+    let (map, start) = (&lab.map, lab.guard);
     let visited: HashSet<Point> = HashSet::from_iter(
         patrol(map, start)
-            .unwrap()
+            .ok()?
             .iter()
             .filter(|&x| *x != start)
             .copied(),
     );
 
-    return visited
+    let obstructions: Vec<Point> = visited
         .par_iter()
-        .map(|(x, y)| {
-            let mut copy: Vec<String> = map.iter().cloned().collect();
-            copy[*y as usize].replace_range((*x as usize)..((*x + 1) as usize), "#");
-            copy
+        .map(|&point| {
+            let mut copy = map.clone();
+            copy[point] = '#';
+            (point, copy)
         })
-        .filter(|map| match patrol(map, start) {
-            PatrolResult::Ok(_) => false,
-            PatrolResult::Loop => true,
+        .filter_map(|(point, map)| match patrol(&map, start) {
+            PatrolResult::Ok(_) => None,
+            PatrolResult::Loop => Some(point),
         })
-        .count() as u32;
+        .collect();
+    debug!(
+        "{} of {} obstructions trap the guard",
+        obstructions.len(),
+        visited.len()
+    );
+    Some(obstructions)
 }
 
-fn patrol(map: &Vec<String>, start: (i32, i32)) -> PatrolResult<HashSet<Point>> {
-    let mut directions = [(0, -1), (1, 0), (0, 1), (-1, 0)].iter().cycle();
-
+fn patrol(map: &Grid<char>, start: Point) -> PatrolResult<HashSet<Point>> {
     let mut current = start;
-    let mut direction = *directions.next().unwrap();
+    let mut direction = Direction::North;
     let mut all_visited: HashSet<VisitedPoint> = HashSet::new();
+    // Where the guard turned, which catches loops where it turns on the spot
+    let mut turns: HashSet<VisitedPoint> = HashSet::new();
     loop {
         match walk(map, current, direction) {
             WalkResult::Turn((visited, next)) => {
-                if visited.par_iter().any(|vp| all_visited.contains(vp)) {
+                if !turns.insert((next, direction))
+                    || visited.par_iter().any(|vp| all_visited.contains(vp))
+                {
+                    trace!("guard loops at {next:?} facing {direction:?}");
                     return PatrolResult::Loop;
                 }
+                trace!("guard turns at {next:?} after {} steps", visited.len());
 
                 all_visited.extend::<HashSet<VisitedPoint>>(HashSet::from_iter(visited));
                 current = next;
-                direction = *directions.next().unwrap();
+                direction = direction.turn_right();
             }
             WalkResult::Stop((visited, _)) => {
                 all_visited.extend::<HashSet<VisitedPoint>>(HashSet::from_iter(visited));
@@ -99,44 +192,67 @@ fn patrol(map: &Vec<String>, start: (i32, i32)) -> PatrolResult<HashSet<Point>>
             }
         }
     }
-    return PatrolResult::Ok(all_visited.iter().map(|(p, _)| p).unique().copied().collect());
+    PatrolResult::Ok(
+        all_visited
+            .iter()
+            .map(|(p, _)| p)
+            .unique()
+            .copied()
+            .collect(),
+    )
 }
 
 fn walk(
-    map: &Vec<String>,
-    start: (i32, i32),
-    direction: (i32, i32),
-) -> WalkResult<(Vec<VisitedPoint>, (i32, i32))> {
+    map: &Grid<char>,
+    start: Point,
+    direction: Direction,
+) -> WalkResult<(Vec<VisitedPoint>, Point)> {
     let mut current = start;
     let mut visited: Vec<VisitedPoint> = Vec::new();
 
-    while let Some(next) = get_map(map, (current.0 + direction.0, current.1 + direction.1)) {
+    while let Some(next) = map.get(current.step(direction)) {
         match next {
             '#' => return WalkResult::Turn((visited, current)),
             _ => {
                 visited.push((current, direction));
-                current = (current.0 + direction.0, current.1 + direction.1);
+                current = current.step(direction);
             }
         }
     }
 
     visited.push((current, direction));
-    return WalkResult::Stop((visited, current));
+    WalkResult::Stop((visited, current))
 }
 
-fn starting_position(map: &Vec<String>) -> (i32, i32) {
-    let pattern = Regex::new(r"\^").unwrap();
-    map.iter()
-        .enumerate()
-        .filter_map(|(y, row)| match pattern.find(row) {
-            Some(m) => return Some((m.start() as i32, y as i32)),
-            None => return None,
-        })
-        .next()
-        .unwrap()
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
 
-fn read_map(file_path: &str) -> Vec<String> {
-    let buffer = read_to_string(file_path).unwrap();
-    return buffer.trim().split("\n").map(|x| x.to_string()).collect();
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            part_1(&Day6::parse(&read_example(6, "test")).unwrap()),
+            Some(41)
+        );
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(
+            part_2(&Day6::parse(&read_example(6, "test")).unwrap()),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn walled_in_guard() {
+        let lab = Day6::parse(".#.\n#^#\n.#.\n").unwrap();
+        assert_eq!(part_1(&lab), None);
+        assert_eq!(part_2(&lab), None);
+        // Used to try boxed in starts forever
+        let generated = Day6::generate(&mut Generator::new(208, 1));
+        let lab = Day6::parse(&generated.input).unwrap();
+        assert!(part_1(&lab).is_some());
+    }
 }
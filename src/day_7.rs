@@ -1,103 +1,218 @@
-use std::{fs::read_to_string, time::Instant};
-
 use itertools::Itertools;
+use num_traits::Zero;
 use rayon::prelude::*;
-use regex::Regex;
 
+use crate::{
+    arith,
+    digits::Digits,
+    generate::{Generated, Generator},
+    parse::Source,
+    Answer, Result, Solution,
+};
+
+#[cfg(not(feature = "bigint"))]
 type Number = i64;
+#[cfg(feature = "bigint")]
+type Number = num_bigint::BigInt;
+
+/// Undoes an operator: given its result and right operand, the left operand
+type Operator = fn(&Number, &Number) -> Option<Number>;
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Vec<(Number, Vec<Number>)>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_input(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
+    }
 
-fn main() {
-    let test = read_input("./input/day_7.test.txt");
-    let input = read_input("./input/day_7.txt");
-
-    println!("First part test value: {} == 3749", part_1(&test));
-    println!("Second part test value: {} == 11387", part_2(&test));
-    
-    let before = Instant::now();
-    println!("First part value: {}", part_1(&input));
-    println!("Second part value: {}", part_2(&input));
-    println!("Work time: {:.2?}", before.elapsed());
+    fn generate(generator: &mut Generator) -> Generated {
+        let mut input = String::new();
+        for _ in 0..generator.size() {
+            let numbers: Vec<i64> = (0..generator.number(2..=6))
+                .map(|_| generator.number(1..100))
+                .collect();
+            // Half the equations hold with some choice of operators
+            let value = match generator.chance(0.5) {
+                true => numbers[1..].iter().fold(numbers[0], |total, &number| {
+                    match generator.number(0..3) {
+                        0 => total + number,
+                        1 => total * number,
+                        _ => format!("{total}{number}").parse().unwrap(),
+                    }
+                }),
+                false => generator.number(1..1_000_000),
+            };
+            input.push_str(&format!("{value}: {}\n", numbers.iter().join(" ")));
+        }
+        Generated::new(input)
+    }
 }
 
-fn part_1(input: &Vec<(Number, Vec<Number>)>) -> Number {
+fn part_1(input: &[(Number, Vec<Number>)]) -> Number {
     evaluate(input, &[inv_add, inv_mul])
 }
 
-fn part_2(input: &Vec<(Number, Vec<Number>)>) -> Number {
+fn part_2(input: &[(Number, Vec<Number>)]) -> Number {
     evaluate(input, &[inv_add, inv_mul, inv_concat])
 }
 
-fn evaluate(
-    input: &Vec<(Number, Vec<Number>)>,
-    operators: &[fn(Number, Number) -> Option<Number>],
-) -> Number {
+fn evaluate(input: &[(Number, Vec<Number>)], operators: &[Operator]) -> Number {
     input
         .par_iter()
-        .filter_map(|(g, ns)| {
+        .filter(|(g, ns)| {
             let numbers = ns.iter().rev().cloned().collect_vec();
-            return match is_traceable(*g, &numbers, operators) {
-                true => Some(*g),
-                false => None,
-            };
+            is_traceable(g, &numbers, operators)
         })
-        .sum()
-}
-
-fn digit_count(number: Number) -> u32 {
-    f64::log10(number as f64) as u32 + 1
-}
-
-fn unconcat(a: Number, b: Number) -> (Number, Number) {
-    let (c, d) = (a - b, Number::pow(10, digit_count(b)));
-    (c / d, c % d)
+        .map(|(g, _)| g)
+        .cloned()
+        .reduce(Number::zero, |a, b| arith::add(&a, &b))
 }
 
-fn inv_mul(a: Number, b: Number) -> Option<Number> {
+fn inv_mul(a: &Number, b: &Number) -> Option<Number> {
+    if b.is_zero() {
+        return None;
+    }
     let (q, r) = (a / b, a % b);
-    return if r == 0 { Some(q) } else { None };
+    if r.is_zero() {
+        Some(q)
+    } else {
+        None
+    }
 }
 
-fn inv_add(a: Number, b: Number) -> Option<Number> {
-    let d = a - b;
-    return if d < 0 { None } else { Some(d) };
+fn inv_add(a: &Number, b: &Number) -> Option<Number> {
+    if a < b {
+        None
+    } else {
+        Some(arith::sub(a, b))
+    }
 }
 
-fn inv_concat(a: Number, b: Number) -> Option<Number> {
-    let (cq, cr) = unconcat(a, b);
-    return if cr == 0 { Some(cq) } else { None };
+fn inv_concat(a: &Number, b: &Number) -> Option<Number> {
+    a.strip_suffix(b)
 }
 
-fn is_traceable(
-    number: Number,
-    numbers: &[Number],
-    operators: &[fn(Number, Number) -> Option<Number>],
-) -> bool {
-    let (head, tail) = (numbers[0], &numbers[1..]);
-    if tail.len() < 1 {
+fn is_traceable(number: &Number, numbers: &[Number], operators: &[Operator]) -> bool {
+    let (head, tail) = (&numbers[0], &numbers[1..]);
+    if tail.is_empty() {
         return head == number;
     }
     operators
         .iter()
         .filter_map(|function| function(number, head))
-        .any(|new_number| is_traceable(new_number, tail, operators))
+        .any(|new_number| is_traceable(&new_number, tail, operators))
 }
 
-fn read_input(file_path: &str) -> Vec<(Number, Vec<Number>)> {
-    let input_pattern = Regex::new(r"(\d+): ((?:\d+\s?)+)").unwrap();
-    read_to_string(file_path)
-        .unwrap()
-        .trim()
-        .split("\n")
-        .par_bridge()
-        .map(|s| {
-            let (_, [first, second]) = input_pattern.captures(s).unwrap().extract();
-            (
-                first.parse::<Number>().unwrap(),
-                second
-                    .split(" ")
-                    .map(|p| p.trim().parse::<Number>().unwrap())
-                    .collect(),
-            )
+fn read_input(input: &str) -> Result<Vec<(Number, Vec<Number>)>> {
+    let source = Source::new(input);
+    source
+        .lines()
+        .map(|line| {
+            let (first, second) = source.split_once(line, ": ")?;
+            let numbers = source.numbers(second.trim_end(), &[' '])?;
+            match numbers.is_empty() {
+                true => Err(source.unexpected(line, "numbers after the test value")),
+                false => Ok((source.number(first)?, numbers)),
+            }
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::utilities::read_example;
+
+    /// Whether some choice of operators, applied left to right, makes `target`
+    fn brute_force(target: &Number, numbers: &[Number], concatenate: bool) -> bool {
+        let mut results = numbers[..1].to_vec();
+        for number in &numbers[1..] {
+            results = results
+                .into_iter()
+                .flat_map(|result| {
+                    let mut next = vec![arith::add(&result, number), arith::mul(&result, number)];
+                    if concatenate {
+                        next.push(format!("{result}{number}").parse().unwrap());
+                    }
+                    next
+                })
+                .collect();
+        }
+        results.contains(target)
+    }
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            Day7::part_1(&Day7::parse(&read_example(7, "test")).unwrap()),
+            3749.into()
+        );
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(
+            Day7::part_2(&Day7::parse(&read_example(7, "test")).unwrap()),
+            11387.into()
+        );
+    }
+
+    #[test]
+    fn degenerate_equations() {
+        let Err(error) = Day7::parse("190: \n") else {
+            panic!("an equation without numbers parsed");
+        };
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: unexpected `190: `, expected numbers after the test value"
+        );
+        let input = Day7::parse("5: 0 0\n").unwrap();
+        assert_eq!(Day7::part_1(&input), 0.into());
+    }
+
+    proptest! {
+        #[test]
+        fn traces_like_brute_force(
+            numbers in prop::collection::vec((1..100_u8).prop_map(Number::from), 1..6),
+            choices in prop::collection::vec(0..3_usize, 5),
+            target in (1..100_000_u32).prop_map(Number::from),
+            reachable: bool,
+        ) {
+            // Half of the targets are made by the operators, as few random
+            // numbers are
+            let mut rest = numbers.iter().cloned();
+            let first = rest.next().unwrap();
+            let target = match reachable {
+                true => rest.zip(&choices).fold(first, |result, (n, c)| match c {
+                    0 => arith::add(&result, &n),
+                    1 => arith::mul(&result, &n),
+                    _ => format!("{result}{n}").parse().unwrap(),
+                }),
+                false => target,
+            };
+            let reversed = numbers.iter().rev().cloned().collect_vec();
+            prop_assert_eq!(
+                is_traceable(&target, &reversed, &[inv_add, inv_mul]),
+                brute_force(&target, &numbers, false)
+            );
+            prop_assert_eq!(
+                is_traceable(&target, &reversed, &[inv_add, inv_mul, inv_concat]),
+                brute_force(&target, &numbers, true)
+            );
+        }
+    }
+}
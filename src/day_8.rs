@@ -1,31 +1,73 @@
 use itertools::Itertools;
-use std::fs::read_to_string;
 
-type RadioAntenna = (char, i32, i32);
+use crate::{
+    generate::{Generated, Generator},
+    grid::Grid,
+    parse::Source,
+    point::Point,
+    render::{Canvas, Color},
+    Answer, Part, Result, Solution,
+};
 
-fn main() {
-    let test = read_input("./input/day_8.test.txt");
-    let input = read_input("./input/day_8.txt");
+type RadioAntenna = (char, Point);
 
-    println!("First part test value: {} == 14", part_1(&test.1, test.0));
-    println!("Second part test value: {} == 34", part_2(&test.1, test.0));
-    println!("First part value: {}", part_1(&input.1, input.0));
-    println!("Second part value: {}", part_2(&input.1, input.0));
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Source::new(input).grid(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let side = generator.size() + 4;
+        let mut map = Grid::new(side, side, '.');
+        let mut frequencies: Vec<char> = ('a'..='z').chain('A'..='Z').chain('0'..='9').collect();
+        generator.shuffle(&mut frequencies);
+        for &frequency in &frequencies[..1 + side / 4] {
+            for _ in 0..generator.number(2..=4) {
+                let free = map.find_all(|&c| c == '.').collect_vec();
+                map[*generator.pick(&free)] = frequency;
+            }
+        }
+        Generated::new(map.to_string() + "\n")
+    }
+
+    fn render(input: &Self::Input, part: Part) -> Option<Canvas> {
+        let antinodes = match part {
+            Part::One => pair_antinodes(input),
+            Part::Two => harmonic_antinodes(input),
+        };
+        let antennas = antennas(input).into_iter().map(|(_, point)| point);
+        Some(
+            Canvas::new(input.clone())
+                .highlight("antenna", Color::Cyan, antennas)
+                .mark("antinode", Color::Red, '#', antinodes),
+        )
+    }
 }
 
-fn eval<F: Fn(RadioAntenna, RadioAntenna) -> Option<Vec<(i32, i32)>>>(
-    input: &Vec<RadioAntenna>,
-    boundaries: (usize, usize),
-    function: &F,
-) -> i32 {
-    let antinodes: Vec<(i32, i32)> = input
+fn eval<F: Fn(Point, Point) -> Option<Vec<Point>>>(map: &Grid<char>, function: &F) -> Vec<Point> {
+    let input = antennas(map);
+    let antinodes: Vec<Point> = input
         .iter()
         .enumerate()
-        .map(|(i, &point)| {
+        .flat_map(|(i, &(frequency, point))| {
             input[i..]
                 .iter()
-                .filter_map(move |&other| {
-                    if point.0 == other.0 {
+                .filter_map(move |&(other_frequency, other)| {
+                    if frequency == other_frequency {
                         function(point, other)
                     } else {
                         None
@@ -33,73 +75,65 @@ fn eval<F: Fn(RadioAntenna, RadioAntenna) -> Option<Vec<(i32, i32)>>>(
                 })
                 .flatten()
         })
-        .flatten()
-        .filter(|&(x, y)| 0 <= x && x < boundaries.0 as i32 && 0 <= y && y < boundaries.1 as i32)
+        .filter(|&point| map.contains(point))
         .unique()
         .collect();
-    antinodes.len() as i32
+    antinodes
+}
+
+fn part_1(map: &Grid<char>) -> i32 {
+    pair_antinodes(map).len() as i32
+}
+
+fn part_2(map: &Grid<char>) -> i32 {
+    harmonic_antinodes(map).len() as i32
 }
 
-fn part_1(input: &Vec<RadioAntenna>, boundaries: (usize, usize)) -> i32 {
-    eval(input, boundaries, &|(_, x, y), (_, ox, oy)| {
-        let (dx, dy) = (ox - x, oy - y);
-        if (dx.abs() + dy.abs()) < 1 {
+/// Antinodes twice as far from one antenna of a pair as from the other
+fn pair_antinodes(map: &Grid<char>) -> Vec<Point> {
+    eval(map, &|point, other| {
+        let delta = other - point;
+        if delta == Point::ORIGIN {
             return None;
         }
-        let points = vec![(x - dx, y - dy), (ox + dx, oy + dy)];
-        return Some(points);
+        let points = vec![point - delta, other + delta];
+        Some(points)
     })
 }
 
-fn part_2(input: &Vec<RadioAntenna>, boundaries: (usize, usize)) -> i32 {
-    eval(input, boundaries, &|(_, x, y), (_, ox, oy)| {
-        let (dx, dy) = (ox - x, oy - y);
-        if (dx.abs() + dy.abs()) < 1 {
+/// Antinodes in line with any pair of antennas, resonant harmonics included
+fn harmonic_antinodes(map: &Grid<char>) -> Vec<Point> {
+    eval(map, &|point, other| {
+        let delta = other - point;
+        if delta == Point::ORIGIN {
             return None;
         }
-        let antinodes = (0..boundaries.0 as i32)
-            .into_iter()
-            .map(|i| vec![(x - dx * i, y - dy * i), (ox + dx * i, oy + dy * i)])
-            .flatten()
+        let antinodes = (0..map.width() as i32)
+            .flat_map(|i| vec![point - delta * i, other + delta * i])
             .collect();
         Some(antinodes)
     })
 }
 
-fn read_input(file_path: &str) -> ((usize, usize), Vec<RadioAntenna>) {
-    let loaded: Vec<String> = read_to_string(file_path)
-        .unwrap()
-        .split("\n")
-        .map(|s| String::from(s.trim()))
-        .collect();
-    let (width, height) = (loaded[0].len(), loaded.len());
-    (
-        (width, height),
-        loaded
-            .iter()
-            .enumerate()
-            .map(|(y, row)| {
-                row.chars()
-                    .enumerate()
-                    .filter_map(move |(x, char)| match char {
-                        '.' => None,
-                        _ => Some((char, x as i32, y as i32)),
-                    })
-            })
-            .flatten()
-            .collect(),
-    )
+fn antennas(map: &Grid<char>) -> Vec<RadioAntenna> {
+    map.iter()
+        .filter(|(_, &c)| c != '.')
+        .map(|(point, &c)| (c, point))
+        .collect()
 }
 
-fn debug_map(points: &Vec<(i32, i32)>, boundaries: (usize, usize)) {
-    let mut map: Vec<String> = (0..boundaries.1)
-        .into_iter()
-        .map(|_| (0..boundaries.0).into_iter().map(|_| '.').join(""))
-        .collect();
-    println!("{:?}", map);
-    for &(x, y) in points {
-        let i: usize = x as usize;
-        map[y as usize].replace_range(i..i + 1, "X");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(part_1(&Day8::parse(&read_example(8, "test")).unwrap()), 14);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(part_2(&Day8::parse(&read_example(8, "test")).unwrap()), 34);
     }
-    println!("{}", map.join("\n"));
 }
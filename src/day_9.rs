@@ -1,37 +1,49 @@
-use std::{fs::read_to_string, num::IntErrorKind};
-
-use itertools::Itertools;
-
-fn main() {
-    let test = read_disk_map("2333133121414131402");
-    println!(
-        "First part test answer: {} == 1928",
-        consume_disk_map_1(&test)
-    );
-    println!(
-        "Second part test answer: {} == 2858",
-        consume_disk_map_2(&test)
-    );
-
-    let input_string = String::from(read_to_string("./input/day_9.txt").unwrap().trim());
-    let input = read_disk_map(&input_string);
-    println!("First part answer: {}", consume_disk_map_1(&input));
-    println!("Second part answer: {}", consume_disk_map_2(&input));
+use crate::{
+    generate::{Generated, Generator},
+    parse::Source,
+    Answer, Result, Solution,
+};
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<Option<u64>>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_disk_map(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        consume_disk_map_1(input).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        consume_disk_map_2(input).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        // Files take one to nine blocks, with up to nine free ones after them
+        let mut input: String = (0..generator.size())
+            .flat_map(|_| [generator.number(1..=9), generator.number(0..=9)])
+            .map(|length: u32| char::from_digit(length, 10).unwrap())
+            .collect();
+        input.push(char::from_digit(generator.number(1..=9), 10).unwrap());
+        Generated::new(input + "\n")
+    }
 }
 
-fn consume_disk_map_1(input: &Vec<Option<u64>>) -> u64 {
+fn consume_disk_map_1(input: &[Option<u64>]) -> u64 {
     let mut a: usize = 0;
     let mut b: usize = input.len() - 1;
-    let mut buffer = input.clone();
+    let mut buffer = input.to_vec();
     while a != b {
         match buffer[a] {
             Some(_) => a += 1,
             None => {
-                match buffer[b] {
-                    Some(_) => {
-                        buffer.swap(a, b);
-                    }
-                    None => {}
+                if buffer[b].is_some() {
+                    buffer.swap(a, b);
                 }
                 b -= 1;
             }
@@ -45,15 +57,16 @@ fn consume_disk_map_1(input: &Vec<Option<u64>>) -> u64 {
         .sum()
 }
 
-fn block_size(input: &Vec<Option<u64>>, start: usize, step: i32, element: Option<u64>) -> usize {
-    let mut i: usize = start;
-    while input[i] == element {
-        i = (i as i32 + step) as usize;
+fn block_size(input: &[Option<u64>], start: usize, step: i32, element: Option<u64>) -> usize {
+    // Signed, so that a block reaching the start of the disk ends before it
+    let mut i = start as i64;
+    while i >= 0 && input.get(i as usize) == Some(&element) {
+        i += step as i64;
     }
-    return (i.abs_diff(start)) as usize;
+    i.abs_diff(start as i64) as usize
 }
 
-fn find_free_space(input: &Vec<Option<u64>>, size: usize, stop: usize) -> Option<usize> {
+fn find_free_space(input: &[Option<u64>], size: usize, stop: usize) -> Option<usize> {
     let mut i = 0;
     while i < stop {
         match input[i] {
@@ -61,69 +74,102 @@ fn find_free_space(input: &Vec<Option<u64>>, size: usize, stop: usize) -> Option
                 let possible = block_size(input, i, 1, None);
                 if possible >= size {
                     return Some(i);
-                } 
+                }
                 i += possible;
             }
-            Some(_) => {i += 1}
+            Some(_) => i += 1,
         }
     }
-    return None
+    None
 }
 
-fn consume_disk_map_2(input: &Vec<Option<u64>>) -> u64 {
+fn consume_disk_map_2(input: &[Option<u64>]) -> u64 {
     let mut i: usize = input.len() - 1;
     let mut k: usize = 0;
-    let mut buffer = input.clone();
+    let mut buffer = input.to_vec();
     while i > k {
         match buffer[i] {
-            None => { i -= 1; },
+            None => {
+                i -= 1;
+            }
             Some(a) => {
                 let size_a = block_size(&buffer, i, -1, Some(a));
-                match find_free_space(&buffer, size_a, i) {
-                    Some(j) => {
-                        for k in 0..size_a {
-                            buffer.swap(j + k, i - k);
-                        }
-                        k = find_free_space(input, 0, i).unwrap();
-                    }
-                    None => {
+                if let Some(j) = find_free_space(&buffer, size_a, i) {
+                    for k in 0..size_a {
+                        buffer.swap(j + k, i - k);
                     }
+                    k = find_free_space(input, 0, i).unwrap_or(i);
                 }
-                i -= size_a;
+                i = i.saturating_sub(size_a);
             }
         }
     }
     buffer
         .iter()
         .enumerate()
-        .filter_map(|(i, j)| match j {
-            Some(k) => Some(i as u64 * *k),
-            None => None,
-        } )
+        .filter_map(|(i, j)| j.as_ref().map(|k| i as u64 * *k))
         .sum()
 }
 
-fn read_disk_map(map_string: &str) -> Vec<Option<u64>> {
-    map_string
-        .chars()
+fn read_disk_map(input: &str) -> Result<Vec<Option<u64>>> {
+    let source = Source::new(input);
+    let map_string = input.trim();
+    let blocks: Vec<Option<u64>> = map_string
+        .char_indices()
+        .map(|(offset, c)| match c.to_digit(10) {
+            Some(count) => Ok(count as usize),
+            None => Err(source.unexpected(&map_string[offset..offset + c.len_utf8()], "a digit")),
+        })
+        .collect::<Result<Vec<usize>>>()?
+        .into_iter()
         .enumerate()
-        .map(|(i, c)| {
+        .map(|(i, count)| {
             let (q, r) = (i / 2, i % 2);
-            let count = c.to_digit(10).unwrap() as usize;
             if r == 0 {
                 (Some(q as u64), count)
             } else {
                 (None, count)
             }
         })
-        .map(|(id, count)| vec![id; count])
-        .flatten()
-        .collect()
+        .flat_map(|(id, count)| vec![id; count])
+        .collect();
+    match blocks.is_empty() {
+        true => Err(source.missing("a disk map with blocks")),
+        false => Ok(blocks),
+    }
 }
 
-fn debug_map(input: &Vec<Option<u64>>) {
-    println!("{}", input.iter().map(|a| match a {
-        None => String::from("."),
-        Some(i) => i.to_string()
-    }).join(""));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            consume_disk_map_1(&Day9::parse(&read_example(9, "test")).unwrap()),
+            1928
+        );
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(
+            consume_disk_map_2(&Day9::parse(&read_example(9, "test")).unwrap()),
+            2858
+        );
+    }
+
+    #[test]
+    fn full_disk() {
+        assert_eq!(consume_disk_map_2(&Day9::parse("609").unwrap()), 90);
+    }
+
+    #[test]
+    fn empty_disk() {
+        for input in ["", "\n", "000"] {
+            assert!(Day9::parse(input).is_err(), "{input:?}");
+        }
+        assert_eq!(consume_disk_map_1(&Day9::parse("10").unwrap()), 0);
+    }
 }
@@ -0,0 +1,177 @@
+use num_traits::Zero;
+
+use crate::arith;
+
+/// Integers as written in base ten. Concatenating and stripping work on
+/// numbers that are not negative; digits are counted without the sign.
+pub trait Digits: Sized {
+    /// Number of digits, one for zero
+    fn digit_count(&self) -> u32;
+
+    /// Ten to the power of `exponent`
+    fn pow10(exponent: u32) -> Self;
+
+    /// The number without its last `count` digits, and those digits. Zero
+    /// and the number itself, when it has no more than `count` digits.
+    fn split_digits(&self, count: u32) -> (Self, Self);
+
+    /// The digits of `self` followed by those of `suffix`
+    fn concat(&self, suffix: &Self) -> Self;
+
+    /// The number that `suffix` concatenates to `self`, if `self` ends with
+    /// its digits. Stripping a number from itself leaves zero.
+    fn strip_suffix(&self, suffix: &Self) -> Option<Self>;
+}
+
+/// The methods that only need arithmetic, given how to make a ten
+macro_rules! digits_from_arithmetic {
+    ($ten:expr) => {
+        fn pow10(exponent: u32) -> Self {
+            arith::pow(&$ten, exponent)
+        }
+
+        fn split_digits(&self, count: u32) -> (Self, Self) {
+            // Ten to the power of the widest count may not fit the type
+            if count >= self.digit_count() {
+                return (Self::zero(), self.clone());
+            }
+            let power = &Self::pow10(count);
+            (self / power, self % power)
+        }
+
+        fn concat(&self, suffix: &Self) -> Self {
+            let shifted = arith::mul(self, &Self::pow10(suffix.digit_count()));
+            arith::add(&shifted, suffix)
+        }
+
+        fn strip_suffix(&self, suffix: &Self) -> Option<Self> {
+            if self < suffix {
+                return None;
+            }
+            let (prefix, rest) = arith::sub(self, suffix).split_digits(suffix.digit_count());
+            rest.is_zero().then_some(prefix)
+        }
+    };
+}
+
+macro_rules! digits_of_unsigned {
+    ($($int:ty),*) => {
+        $(impl Digits for $int {
+            fn digit_count(&self) -> u32 {
+                self.checked_ilog10().map_or(1, |log| log + 1)
+            }
+
+            digits_from_arithmetic!(10);
+        })*
+    };
+}
+
+macro_rules! digits_of_signed {
+    ($($int:ty),*) => {
+        $(impl Digits for $int {
+            fn digit_count(&self) -> u32 {
+                self.unsigned_abs().digit_count()
+            }
+
+            digits_from_arithmetic!(10);
+        })*
+    };
+}
+
+digits_of_unsigned!(u8, u16, u32, u64, u128, usize);
+digits_of_signed!(i8, i16, i32, i64, i128, isize);
+
+#[cfg(feature = "bigint")]
+impl Digits for num_bigint::BigUint {
+    fn digit_count(&self) -> u32 {
+        self.to_string().len() as u32
+    }
+
+    digits_from_arithmetic!(Self::from(10_u8));
+}
+
+#[cfg(feature = "bigint")]
+impl Digits for num_bigint::BigInt {
+    fn digit_count(&self) -> u32 {
+        self.magnitude().digit_count()
+    }
+
+    digits_from_arithmetic!(Self::from(10_u8));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks every function at each power of ten a type can hold, and the
+    /// numbers right around it, against the decimal text of the numbers
+    macro_rules! boundaries {
+        ($($int:ty),*) => {
+            $({
+                let mut numbers = vec![0, 1, <$int>::MAX, <$int>::MIN];
+                let mut power: $int = 1;
+                while let Some(next) = power.checked_mul(10) {
+                    power = next;
+                    numbers.extend([power - 1, power, power + 1]);
+                }
+                for number in numbers {
+                    let text = number.to_string();
+                    let digits = text.trim_start_matches('-');
+                    assert_eq!(number.digit_count() as usize, digits.len(), "{number}");
+                    if number < 0 as $int {
+                        continue;
+                    }
+                    for count in 0..=digits.len() + 1 {
+                        let (prefix, suffix) = digits.split_at(digits.len().saturating_sub(count));
+                        let suffix: $int = suffix.parse().unwrap_or(0);
+                        let prefix: $int = prefix.parse().unwrap_or(0);
+                        assert_eq!(number.split_digits(count as u32), (prefix, suffix), "{number}");
+                    }
+                    let (prefix, suffix) = number.split_digits(number.digit_count() / 2);
+                    if suffix.digit_count() == number.digit_count() / 2 {
+                        assert_eq!(prefix.concat(&suffix), number, "{number}");
+                        assert_eq!(number.strip_suffix(&suffix), Some(prefix), "{number}");
+                    }
+                }
+            })*
+        };
+    }
+
+    #[test]
+    fn powers_of_ten() {
+        boundaries!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+    }
+
+    #[test]
+    fn concatenation() {
+        assert_eq!(12_u64.concat(&345), 12345);
+        assert_eq!(0_u64.concat(&7), 7);
+        assert_eq!(7_u64.concat(&0), 70);
+        assert_eq!(12345_u64.strip_suffix(&345), Some(12));
+        assert_eq!(12345_u64.strip_suffix(&12345), Some(0));
+        assert_eq!(12345_u64.strip_suffix(&45), Some(123));
+        assert_eq!(12345_u64.strip_suffix(&44), None);
+        assert_eq!(45_u64.strip_suffix(&12345), None);
+        assert_eq!(1000_u64.strip_suffix(&0), Some(100));
+        assert_eq!(u128::MAX.digit_count(), 39);
+        assert_eq!(i128::MIN.digit_count(), 39);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn big_integers() {
+        use num_bigint::{BigInt, BigUint};
+
+        let mut power = BigUint::from(1_u8);
+        for exponent in 0..60 {
+            assert_eq!(power.digit_count(), exponent + 1);
+            assert_eq!((&power - 1_u8).digit_count(), exponent.max(1));
+            assert_eq!(BigUint::pow10(exponent), power);
+            power *= 10_u8;
+        }
+        let big: BigInt = "-123456789012345678901234567890".parse().unwrap();
+        assert_eq!(big.digit_count(), 30);
+        let (prefix, suffix) = (-&big).split_digits(10);
+        assert_eq!(prefix.concat(&suffix), -big);
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    any::Any,
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Position in a puzzle input, with one-based line and column
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    pub file: Option<PathBuf>,
+    /// Name of the blank-line-separated section the position is in
+    pub section: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}:{}", file.display(), self.line, self.column),
+            None => write!(f, "line {}, column {}", self.line, self.column),
+        }?;
+        match &self.section {
+            Some(section) => write!(f, " (in {section})"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("unable to read {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{location}: unexpected `{token}`, expected {expected}")]
+    UnexpectedToken {
+        location: Location,
+        token: String,
+        expected: String,
+    },
+    #[error("{location}: bad number `{token}`: {reason}")]
+    BadNumber {
+        location: Location,
+        token: String,
+        reason: String,
+    },
+    #[error("{location}: missing {section}")]
+    MissingSection { location: Location, section: String },
+    /// A puzzle parameter that the day does not declare, or whose value
+    /// does not parse
+    #[error("bad parameter `{name}`: {reason}")]
+    BadParam { name: String, reason: String },
+    /// A solver that panicked instead of returning an answer
+    #[error("panicked: {reason}")]
+    Panicked { reason: String },
+    /// Arithmetic that overflowed, with the `checked` feature
+    #[error("arithmetic overflow computing {operation}")]
+    Overflow { operation: String },
+    /// A solver that was given up on, still running
+    #[error("no answer after {limit:.2?}")]
+    TimedOut { limit: Duration },
+    /// A file that would be overwritten
+    #[error("{} already exists", path.display())]
+    Exists { path: PathBuf },
+    /// An HTTP server that could not start listening
+    #[error("unable to serve on {address}: {reason}")]
+    Serve { address: String, reason: String },
+    /// A manifest or other file of settings that failed to deserialize
+    #[error("invalid {}: {reason}", path.display())]
+    Invalid { path: PathBuf, reason: String },
+}
+
+impl Error {
+    /// The error of a solver that panicked with `payload`
+    pub fn panicked(payload: Box<dyn Any + Send>) -> Self {
+        let reason = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "unknown cause".to_owned(),
+            },
+        };
+        Error::Panicked { reason }
+    }
+
+    /// Names the input file the error was found in
+    pub fn in_file(mut self, path: &Path) -> Self {
+        match &mut self {
+            Error::Io { .. }
+            | Error::BadParam { .. }
+            | Error::Panicked { .. }
+            | Error::Overflow { .. }
+            | Error::TimedOut { .. }
+            | Error::Exists { .. }
+            | Error::Serve { .. }
+            | Error::Invalid { .. } => {}
+            Error::UnexpectedToken { location, .. }
+            | Error::BadNumber { location, .. }
+            | Error::MissingSection { location, .. } => location.file = Some(path.to_owned()),
+        }
+        self
+    }
+}
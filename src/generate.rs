@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+
+use rand::{
+    distr::uniform::{SampleRange, SampleUniform},
+    prelude::*,
+};
+
+use crate::{
+    grid::Grid,
+    params::Params,
+    point::{Direction, Point},
+};
+
+/// Random source for making up puzzle inputs. The same seed and size always
+/// make the same input, so a failing one can be made again.
+pub struct Generator {
+    rng: StdRng,
+    size: usize,
+}
+
+impl Generator {
+    pub fn new(seed: u64, size: usize) -> Self {
+        Generator {
+            rng: StdRng::seed_from_u64(seed),
+            size: size.max(1),
+        }
+    }
+
+    /// How big the input should be: the side of a map, the length of a list,
+    /// or the number of bits of a circuit, depending on the day
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// A number picked uniformly out of `range`
+    pub fn number<T, R>(&mut self, range: R) -> T
+    where
+        T: SampleUniform,
+        R: SampleRange<T>,
+    {
+        self.rng.random_range(range)
+    }
+
+    /// True with the given probability
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.rng.random_bool(probability.clamp(0.0, 1.0))
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        items.choose(&mut self.rng).expect("nothing to pick from")
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        items.shuffle(&mut self.rng);
+    }
+
+    /// A word of `length` characters picked out of `alphabet`
+    pub fn word(&mut self, alphabet: &str, length: usize) -> String {
+        let alphabet: Vec<char> = alphabet.chars().collect();
+        (0..length).map(|_| *self.pick(&alphabet)).collect()
+    }
+
+    /// A perfect maze of `width` by `height` rooms, carved by a random
+    /// depth-first search from the top left room. Rooms sit at odd positions
+    /// of the map, with walls all around it.
+    pub fn maze(&mut self, width: usize, height: usize) -> Maze {
+        let mut map = Grid::new(2 * width + 1, 2 * height + 1, '#');
+        let room = |x: usize, y: usize| Point::new(2 * x as i32 + 1, 2 * y as i32 + 1);
+        let rooms = Grid::new(width, height, ());
+
+        let mut depths = Grid::new(width, height, 0);
+        let mut parents = Grid::new(width, height, None);
+        let mut seen = HashSet::from([Point::ORIGIN]);
+        let mut stack = vec![Point::ORIGIN];
+        map[room(0, 0)] = '.';
+        while let Some(&current) = stack.last() {
+            let unseen: Vec<Point> = rooms
+                .neighbors(current)
+                .map(|(next, _)| next)
+                .filter(|next| !seen.contains(next))
+                .collect();
+            if unseen.is_empty() {
+                stack.pop();
+                continue;
+            }
+            let next = *self.pick(&unseen);
+            let (from, to) = (
+                room(current.x as usize, current.y as usize),
+                room(next.x as usize, next.y as usize),
+            );
+            map[to] = '.';
+            map[Point::new((from.x + to.x) / 2, (from.y + to.y) / 2)] = '.';
+            depths[next] = depths[current] + 1;
+            parents[next] = Some(current);
+            seen.insert(next);
+            stack.push(next);
+        }
+        Maze {
+            map,
+            depths,
+            parents,
+        }
+    }
+}
+
+/// A maze made by [`Generator::maze`], which remembers how it was carved
+pub struct Maze {
+    pub map: Grid<char>,
+    depths: Grid<usize>,
+    parents: Grid<Option<Point>>,
+}
+
+impl Maze {
+    /// Position on the map of a room
+    pub fn room(&self, room: Point) -> Point {
+        Point::new(2 * room.x + 1, 2 * room.y + 1)
+    }
+
+    /// The room furthest from the top left one
+    pub fn deepest_room(&self) -> Point {
+        self.depths
+            .iter()
+            .max_by_key(|&(_, depth)| depth)
+            .map(|(room, _)| room)
+            .unwrap()
+    }
+
+    /// Tiles of the only way from the top left room to `room`, both included
+    pub fn path_to(&self, room: Point) -> Vec<Point> {
+        let mut path = vec![self.room(room)];
+        let mut current = room;
+        while let Some(parent) = self.parents[current] {
+            let (from, to) = (self.room(current), self.room(parent));
+            path.push(Point::new((from.x + to.x) / 2, (from.y + to.y) / 2));
+            path.push(to);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Knocks down `count` walls between rooms, giving the maze loops
+    pub fn knock_down(&mut self, generator: &mut Generator, count: usize) {
+        let walls: Vec<Point> = self
+            .map
+            .positions()
+            .filter(|&point| {
+                // Walls between rooms are the ones at an odd position
+                self.map[point] == '#'
+                    && (point.x + point.y) % 2 == 1
+                    && [Direction::East, Direction::South]
+                        .iter()
+                        .any(|&direction| {
+                            let (a, b) = (point.step(direction), point.step(direction.reverse()));
+                            self.map.get(a) == Some(&'.') && self.map.get(b) == Some(&'.')
+                        })
+            })
+            .collect();
+        for _ in 0..count.min(walls.len()) {
+            let wall = *generator.pick(&walls);
+            self.map[wall] = '.';
+        }
+    }
+}
+
+/// A made-up puzzle input, with the parameters the day needs to solve it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub input: String,
+    pub params: Params,
+}
+
+impl Generated {
+    pub fn new(input: String) -> Self {
+        Generated {
+            input,
+            params: Params::default(),
+        }
+    }
+
+    /// Sets a parameter the input cannot be solved without
+    pub fn with(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.set(name, value.to_string());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Part, DAYS};
+
+    #[test]
+    fn seeds_repeat() {
+        for day in DAYS {
+            let generated = day.generate(7, 6);
+            assert_eq!(generated, day.generate(7, 6), "day {}", day.day());
+        }
+        assert_ne!(DAYS[0].generate(1, 6), DAYS[0].generate(2, 6));
+    }
+
+    #[test]
+    fn every_day_solves_its_inputs() {
+        for day in DAYS {
+            for seed in 0..3 {
+                let generated = day.generate(seed, 6);
+                let answers = day.solve(&generated.input, &Part::BOTH, &generated.params);
+                assert!(
+                    answers.is_ok(),
+                    "day {} seed {seed}: {}",
+                    day.day(),
+                    answers.unwrap_err()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// Handle of a node interned in a [`Graph`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+/// Graph whose nodes are interned, so each value is stored once and edges
+/// refer to it by [`NodeId`]. In an undirected graph every edge goes both ways.
+#[derive(Debug, Clone)]
+pub struct Graph<T> {
+    directed: bool,
+    nodes: Vec<T>,
+    ids: HashMap<T, NodeId>,
+    outgoing: Vec<HashSet<NodeId>>,
+    incoming: Vec<HashSet<NodeId>>,
+}
+
+impl<T: Clone + Eq + Hash> Graph<T> {
+    fn new(directed: bool) -> Self {
+        Graph {
+            directed,
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+            outgoing: Vec::new(),
+            incoming: Vec::new(),
+        }
+    }
+
+    pub fn directed() -> Self {
+        Graph::new(true)
+    }
+
+    pub fn undirected() -> Self {
+        Graph::new(false)
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Interns `node`, returning the id it already had if it was added before
+    pub fn add_node(&mut self, node: T) -> NodeId {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        let id = NodeId(self.nodes.len());
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.outgoing.push(HashSet::new());
+        self.incoming.push(HashSet::new());
+        id
+    }
+
+    /// Adds an edge from `a` to `b`, and from `b` to `a` when undirected
+    pub fn add_edge(&mut self, a: T, b: T) -> (NodeId, NodeId) {
+        let (a, b) = (self.add_node(a), self.add_node(b));
+        self.outgoing[a.0].insert(b);
+        self.incoming[b.0].insert(a);
+        if !self.directed {
+            self.outgoing[b.0].insert(a);
+            self.incoming[a.0].insert(b);
+        }
+        (a, b)
+    }
+
+    pub fn id(&self, node: &T) -> Option<NodeId> {
+        self.ids.get(node).copied()
+    }
+
+    pub fn node(&self, id: NodeId) -> &T {
+        &self.nodes[id.0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Every node, in the order they were added
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.nodes.len()).map(NodeId)
+    }
+
+    pub fn has_edge(&self, a: NodeId, b: NodeId) -> bool {
+        self.outgoing[a.0].contains(&b)
+    }
+
+    /// Nodes the edges out of `id` lead to
+    pub fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.outgoing[id.0].iter().copied()
+    }
+
+    /// Nodes with an edge into `id`
+    pub fn predecessors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.incoming[id.0].iter().copied()
+    }
+
+    /// Number of edges out of `id`
+    pub fn degree(&self, id: NodeId) -> usize {
+        self.outgoing[id.0].len()
+    }
+
+    /// Number of edges into `id`
+    pub fn in_degree(&self, id: NodeId) -> usize {
+        self.incoming[id.0].len()
+    }
+
+    /// The graph made of `nodes` and the edges between them
+    pub fn subgraph<'a>(&self, nodes: impl IntoIterator<Item = &'a T>) -> Graph<T>
+    where
+        T: 'a,
+    {
+        let mut subgraph = Graph::new(self.directed);
+        let kept: Vec<NodeId> = nodes
+            .into_iter()
+            .filter_map(|node| {
+                subgraph.add_node(node.clone());
+                self.id(node)
+            })
+            .collect();
+        for &a in &kept {
+            for b in self.neighbors(a).filter(|b| kept.contains(b)) {
+                subgraph.add_edge(self.node(a).clone(), self.node(b).clone());
+            }
+        }
+        subgraph
+    }
+
+    /// Groups of nodes linked to each other, ignoring which way edges go
+    pub fn connected_components(&self) -> Vec<Vec<NodeId>> {
+        let mut seen = vec![false; self.len()];
+        let mut components = Vec::new();
+        for start in self.nodes() {
+            if seen[start.0] {
+                continue;
+            }
+            seen[start.0] = true;
+            let mut component = vec![start];
+            let mut queue = VecDeque::from([start]);
+            while let Some(id) = queue.pop_front() {
+                for next in self.neighbors(id).chain(self.predecessors(id)) {
+                    if !seen[next.0] {
+                        seen[next.0] = true;
+                        component.push(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Orders the nodes so that every edge points forward, or `None` when a
+    /// cycle makes that impossible. Ties keep the order nodes were added in.
+    pub fn topological_sort(&self) -> Option<Vec<NodeId>> {
+        let mut in_degrees: Vec<usize> = self.nodes().map(|id| self.in_degree(id)).collect();
+        let mut queue: VecDeque<NodeId> = self.nodes().filter(|id| in_degrees[id.0] == 0).collect();
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(id) = queue.pop_front() {
+            sorted.push(id);
+            let mut next: Vec<NodeId> = self.neighbors(id).collect();
+            next.sort();
+            for next in next {
+                in_degrees[next.0] -= 1;
+                if in_degrees[next.0] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+        (sorted.len() == self.len()).then_some(sorted)
+    }
+
+    /// Whether following the edges can lead back to where it started
+    pub fn has_cycle(&self) -> bool {
+        match self.directed {
+            true => self.topological_sort().is_none(),
+            // Without cycles, every component of an undirected graph is a tree
+            false => {
+                let edges = self.nodes().map(|id| self.degree(id)).sum::<usize>() / 2;
+                edges + self.connected_components().len() > self.len()
+            }
+        }
+    }
+
+    /// Every clique that no other node could join, in an undirected graph
+    pub fn maximal_cliques(&self) -> Vec<Vec<NodeId>> {
+        let mut cliques = Vec::new();
+        self.bron_kerbosch(
+            Vec::new(),
+            self.nodes().collect(),
+            HashSet::new(),
+            &mut cliques,
+        );
+        cliques
+    }
+
+    fn bron_kerbosch(
+        &self,
+        clique: Vec<NodeId>,
+        mut candidates: HashSet<NodeId>,
+        mut excluded: HashSet<NodeId>,
+        cliques: &mut Vec<Vec<NodeId>>,
+    ) {
+        if candidates.is_empty() && excluded.is_empty() {
+            cliques.push(clique);
+            return;
+        }
+        // Any maximal clique holds the pivot or one of its non-neighbours
+        let pivot = *candidates
+            .union(&excluded)
+            .max_by_key(|&&id| self.degree(id))
+            .unwrap();
+        let branches: Vec<NodeId> = candidates
+            .iter()
+            .filter(|&&id| !self.has_edge(pivot, id))
+            .copied()
+            .collect();
+        for id in branches {
+            let neighbours = &self.outgoing[id.0];
+            let mut grown = clique.clone();
+            grown.push(id);
+            self.bron_kerbosch(
+                grown,
+                candidates.intersection(neighbours).copied().collect(),
+                excluded.intersection(neighbours).copied().collect(),
+                cliques,
+            );
+            candidates.remove(&id);
+            excluded.insert(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names<'a>(graph: &Graph<&'a str>, ids: &[NodeId]) -> Vec<&'a str> {
+        let mut names: Vec<&str> = ids.iter().map(|&id| *graph.node(id)).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn nodes_are_interned() {
+        let mut graph = Graph::undirected();
+        let (a, b) = graph.add_edge("a", "b");
+        assert_eq!(graph.add_node("a"), a);
+        graph.add_edge("b", "c");
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.degree(b), 2);
+        assert!(graph.has_edge(b, a));
+    }
+
+    #[test]
+    fn topological_order() {
+        let mut graph = Graph::directed();
+        graph.add_edge("shirt", "tie");
+        graph.add_edge("tie", "jacket");
+        graph.add_edge("trousers", "shoes");
+        graph.add_edge("shirt", "jacket");
+        let order: Vec<&str> = graph
+            .topological_sort()
+            .unwrap()
+            .into_iter()
+            .map(|id| *graph.node(id))
+            .collect();
+        assert_eq!(order, ["shirt", "trousers", "tie", "shoes", "jacket"]);
+        assert!(!graph.has_cycle());
+
+        graph.add_edge("jacket", "shirt");
+        assert!(graph.topological_sort().is_none());
+        assert!(graph.has_cycle());
+    }
+
+    #[test]
+    fn components_and_cliques() {
+        let mut graph = Graph::undirected();
+        for (a, b) in [("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("e", "f")] {
+            graph.add_edge(a, b);
+        }
+        let components: Vec<Vec<&str>> = graph
+            .connected_components()
+            .iter()
+            .map(|component| names(&graph, component))
+            .collect();
+        assert_eq!(components, [vec!["a", "b", "c", "d"], vec!["e", "f"]]);
+        assert!(graph.has_cycle());
+
+        let mut cliques: Vec<Vec<&str>> = graph
+            .maximal_cliques()
+            .iter()
+            .map(|clique| names(&graph, clique))
+            .collect();
+        cliques.sort();
+        assert_eq!(
+            cliques,
+            [vec!["a", "b", "c"], vec!["c", "d"], vec!["e", "f"]]
+        );
+    }
+}
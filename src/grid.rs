@@ -0,0 +1,221 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::point::{Direction, Point};
+
+/// Rectangular map of cells, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds a grid out of its cells, row by row
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len().is_multiple_of(width),
+            "{} cells do not make rows of {width}",
+            cells.len()
+        );
+        Grid {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.offset(point).is_some()
+    }
+
+    fn offset(&self, point: Point) -> Option<usize> {
+        point
+            .to_indices_within(self.width, self.height)
+            .map(|(x, y)| y * self.width + x)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.offset(point).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.offset(point).map(|offset| &mut self.cells[offset])
+    }
+
+    /// Every position of the grid, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every cell with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Positions of the cells matching `predicate`, row by row
+    pub fn find_all<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Point> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    /// Position of the first cell matching `predicate`
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.find_all(predicate).next()
+    }
+
+    /// Cells one step from `point` in each of `directions`, skipping those
+    /// off the grid
+    fn around<'a>(
+        &'a self,
+        point: Point,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        directions.iter().filter_map(move |&direction| {
+            let next = point.step(direction);
+            self.get(next).map(|cell| (next, cell))
+        })
+    }
+
+    /// The orthogonal neighbours of a point that are on the grid
+    pub fn neighbors(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.around(point, &Direction::ORTHOGONAL)
+    }
+
+    /// The orthogonal and diagonal neighbours of a point that are on the grid
+    pub fn neighbors_8(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.around(point, &Direction::ALL)
+    }
+
+    /// Cells from `start` onwards in `direction`, until the edge of the grid.
+    /// Rows, columns and diagonals are all rays.
+    pub fn ray(&self, start: Point, direction: Direction) -> impl Iterator<Item = (Point, &T)> {
+        std::iter::successors(Some(start), move |&point| Some(point.step(direction)))
+            .map_while(|point| self.get(point).map(|cell| (point, cell)))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn map<U>(&self, cell: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(cell).collect(),
+        }
+    }
+
+    /// Draws the grid one character per cell, with a line per row
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&cell).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point} is off the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point} is off the grid"))
+    }
+}
+
+impl Display for Grid<char> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(|&c| c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::from_cells(3, "abcdefghijkl".chars().collect())
+    }
+
+    #[test]
+    fn signed_access() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&'f'));
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 4)), None);
+        assert_eq!(grid[Point::new(0, 3)], 'j');
+    }
+
+    #[test]
+    fn neighbors_stay_on_the_grid() {
+        let grid = sample();
+        let corner: Vec<_> = grid.neighbors(Point::ORIGIN).map(|(_, &c)| c).collect();
+        assert_eq!(corner, ['b', 'd']);
+        assert_eq!(grid.neighbors(Point::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbors_8(Point::ORIGIN).count(), 3);
+        assert_eq!(grid.neighbors_8(Point::new(1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn lines() {
+        let grid = sample();
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(2).collect::<String>(), "cfil");
+        let diagonal: String = grid
+            .ray(Point::ORIGIN, Direction::SouthEast)
+            .map(|(_, &c)| c)
+            .collect();
+        assert_eq!(diagonal, "aei");
+        assert_eq!(grid.find(|&c| c == 'h'), Some(Point::new(1, 2)));
+        assert_eq!(grid.to_string(), "abc\ndef\nghi\njkl");
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{grid::Grid, Error, Result};
+
+/// Colour of a pixel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+}
+
+/// Picture with a pixel per cell of a grid, or a square of pixels when scaled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Draws every cell of `grid` as a `scale` by `scale` square of the colour
+    /// `color` gives it
+    pub fn from_grid<T>(grid: &Grid<T>, scale: usize, color: impl Fn(&T) -> Rgb) -> Self {
+        assert!(scale > 0, "images need at least a pixel per cell");
+        let (width, height) = (grid.width() * scale, grid.height() * scale);
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in grid.rows() {
+            let line: Vec<Rgb> = row
+                .iter()
+                .flat_map(|cell| [color(cell)].repeat(scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Every pixel, row by row
+    pub fn pixels(&self) -> &[Rgb] {
+        &self.pixels
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&Rgb(r, g, b)| [r, g, b])
+            .collect()
+    }
+
+    /// Writes the image as a binary PPM
+    pub fn write_ppm(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.bytes())
+    }
+
+    /// Writes the image as an 8-bit RGB PNG
+    pub fn write_png(&self, writer: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut png| png.write_image_data(&self.bytes()))
+            .map_err(io::Error::other)
+    }
+
+    /// Saves the image at `path`, as a PNG or a PPM depending on its extension
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        let write = match extension {
+            Some("png") => Image::write_png,
+            Some("ppm") => Image::write_ppm,
+            _ => {
+                return Err(Error::Invalid {
+                    path: path.to_owned(),
+                    reason: "images are saved as .png or .ppm".to_owned(),
+                })
+            }
+        };
+        let io_error = |source| Error::Io {
+            path: path.to_owned(),
+            source,
+        };
+        let mut writer = BufWriter::new(File::create(path).map_err(io_error)?);
+        write(self, &mut writer)
+            .and_then(|()| writer.flush())
+            .map_err(io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image() -> Image {
+        let grid = Grid::from_cells(2, vec![true, false]);
+        Image::from_grid(&grid, 2, |&on| match on {
+            true => Rgb::WHITE,
+            false => Rgb(255, 0, 0),
+        })
+    }
+
+    #[test]
+    fn cells_become_squares() {
+        let image = image();
+        assert_eq!((image.width(), image.height()), (4, 2));
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        let (header, pixels) = ppm.split_at(11);
+        assert_eq!(header, b"P6\n4 2\n255\n");
+        let row = [255, 255, 255, 255, 255, 255, 255, 0, 0, 255, 0, 0];
+        assert_eq!(pixels, [row, row].concat());
+    }
+
+    #[test]
+    fn png_signature() {
+        let mut png = Vec::new();
+        image().write_png(&mut png).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
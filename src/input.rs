@@ -0,0 +1,125 @@
+use std::{
+    env,
+    fs::read_to_string,
+    io::{stdin, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{params::Params, Error, Result};
+
+/// Environment variable naming the folder of the puzzle inputs
+pub const INPUT_DIR: &str = "AOC_INPUT_DIR";
+
+/// Where a puzzle input is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    /// The folder of the puzzle inputs: `$AOC_INPUT_DIR`, or `./input`
+    pub fn directory() -> PathBuf {
+        env::var_os(INPUT_DIR).map_or_else(|| PathBuf::from("./input"), PathBuf::from)
+    }
+
+    /// The puzzle input of a day
+    pub fn of_day(day: u8) -> Input {
+        Input::File(Input::directory().join(format!("day_{day}.txt")))
+    }
+
+    /// A variant of the input of a day, such as `test`, `test.1` or
+    /// `test.small_2`, kept next to it as `day_N.VARIANT.txt`
+    pub fn variant(day: u8, variant: &str) -> Input {
+        Input::File(Input::directory().join(format!("day_{day}.{variant}.txt")))
+    }
+
+    /// Reads an input as given on the command line: `-` for stdin, a path to
+    /// a file, or the name of a variant. Without one, the day's own input.
+    pub fn resolve(day: u8, name: Option<&str>) -> Input {
+        Input::resolve_in(&Input::directory(), day, name)
+    }
+
+    fn resolve_in(directory: &Path, day: u8, name: Option<&str>) -> Input {
+        let Some(name) = name else {
+            return Input::File(directory.join(format!("day_{day}.txt")));
+        };
+        if name == "-" {
+            return Input::Stdin;
+        }
+        // Anything that looks like a file is one; other names are variants
+        let path = Path::new(name);
+        match path.components().count() > 1 || name.ends_with(".txt") || path.is_file() {
+            true => Input::File(path.to_owned()),
+            false => Input::File(directory.join(format!("day_{day}.{name}.txt"))),
+        }
+    }
+
+    /// The path of the file, or `<stdin>`, for messages and reports
+    pub fn path(&self) -> &Path {
+        match self {
+            Input::Stdin => Path::new("<stdin>"),
+            Input::File(path) => path,
+        }
+    }
+
+    /// Reads the whole input. Stdin can only be read once.
+    pub fn read(&self) -> Result<String> {
+        let io_error = |source| Error::Io {
+            path: self.path().to_owned(),
+            source,
+        };
+        match self {
+            Input::Stdin => {
+                let mut text = String::new();
+                stdin().read_to_string(&mut text).map_err(io_error)?;
+                Ok(text)
+            }
+            Input::File(path) => read_to_string(path).map_err(io_error),
+        }
+    }
+
+    /// The parameters of the input from its sidecar, none for stdin
+    pub fn params(&self) -> Result<Params> {
+        match self {
+            Input::Stdin => Ok(Params::default()),
+            Input::File(path) => Params::sidecar(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_resolve_to_inputs() {
+        let directory = Path::new("inputs");
+        let file = |path: &str| Input::File(PathBuf::from(path));
+        assert_eq!(
+            Input::resolve_in(directory, 16, None),
+            file("inputs/day_16.txt")
+        );
+        assert_eq!(Input::resolve_in(directory, 16, Some("-")), Input::Stdin);
+        for (name, path) in [
+            ("test", "inputs/day_16.test.txt"),
+            ("test.1", "inputs/day_16.test.1.txt"),
+            ("test.small_2", "inputs/day_16.test.small_2.txt"),
+            ("test.ex1", "inputs/day_16.test.ex1.txt"),
+        ] {
+            assert_eq!(Input::resolve_in(directory, 16, Some(name)), file(path));
+        }
+        assert_eq!(
+            Input::resolve_in(directory, 16, Some("mine.txt")),
+            file("mine.txt")
+        );
+        assert_eq!(
+            Input::resolve_in(directory, 16, Some("other/day_16")),
+            file("other/day_16")
+        );
+        assert_eq!(
+            Input::resolve_in(directory, 16, Some("Cargo.toml")),
+            file("Cargo.toml")
+        );
+    }
+}
@@ -0,0 +1,80 @@
+pub mod animation;
+pub mod arith;
+pub mod bench;
+pub mod day_1;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_17;
+pub mod day_18;
+pub mod day_19;
+pub mod day_2;
+pub mod day_20;
+pub mod day_21;
+pub mod day_22;
+pub mod day_23;
+pub mod day_24;
+pub mod day_25;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod day_6;
+pub mod day_7;
+pub mod day_8;
+pub mod day_9;
+pub mod digits;
+pub mod error;
+pub mod generate;
+pub mod graph;
+pub mod grid;
+pub mod image;
+pub mod input;
+pub mod manifest;
+pub mod memo;
+pub mod params;
+pub mod parse;
+pub mod point;
+pub mod render;
+pub mod report;
+pub mod scaffold;
+pub mod search;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod solution;
+pub mod utilities;
+
+pub use error::{Error, Result};
+pub use solution::{Answer, Part, Solution, Solved, Solver};
+
+/// Every day's solution, ordered by day
+pub static DAYS: [&dyn Solver; 25] = [
+    &day_1::Day1,
+    &day_2::Day2,
+    &day_3::Day3,
+    &day_4::Day4,
+    &day_5::Day5,
+    &day_6::Day6,
+    &day_7::Day7,
+    &day_8::Day8,
+    &day_9::Day9,
+    &day_10::Day10,
+    &day_11::Day11,
+    &day_12::Day12,
+    &day_13::Day13,
+    &day_14::Day14,
+    &day_15::Day15,
+    &day_16::Day16,
+    &day_17::Day17,
+    &day_18::Day18,
+    &day_19::Day19,
+    &day_20::Day20,
+    &day_21::Day21,
+    &day_22::Day22,
+    &day_23::Day23,
+    &day_24::Day24,
+    &day_25::Day25,
+];
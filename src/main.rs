@@ -0,0 +1,702 @@
+use std::{
+    fs::write,
+    panic::{self, catch_unwind},
+    path::{Path, PathBuf},
+    process::exit,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "serve")]
+use advent_of_code_2024::serve::{Limits, Service};
+use advent_of_code_2024::{
+    animation::{Frames, Recorder},
+    bench::{Baseline, Sampling, Stats},
+    input::Input,
+    manifest::{Manifest, Status},
+    params::Params,
+    render::{Canvas, Style},
+    report::Record,
+    scaffold, Answer, Error, Part, Result, Solved, DAYS,
+};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use log::LevelFilter;
+use rayon::prelude::*;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2024 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Log what the solvers do on stderr: -v for progress, -vv for details,
+    /// -vvv for every step. RUST_LOG, such as
+    /// `advent_of_code_2024::day_6=trace`, takes precedence.
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+}
+
+/// How `run` prints its answers
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// A sentence per answer
+    Text,
+    /// A JSON record per part, one per line
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve the puzzle of a single day, or of every day
+    Run {
+        /// Day of the puzzle, from 1 to 25
+        #[arg(
+            value_parser = clap::value_parser!(u8).range(1..=25),
+            required_unless_present = "all"
+        )]
+        day: Option<u8>,
+        /// Solve every day in parallel and print a summary table
+        #[arg(long, conflicts_with_all = ["day", "input"])]
+        all: bool,
+        /// Only solve the given part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Puzzle input: `-` for stdin, a file, or a variant of the day's
+        /// input such as `test` or `test.1`. Inputs are looked up in
+        /// $AOC_INPUT_DIR, or ./input, as day_N.txt and day_N.VARIANT.txt.
+        #[arg(long, value_name = "INPUT")]
+        input: Option<String>,
+        /// Override a puzzle parameter, on top of the ones in the input's
+        /// `.toml` sidecar
+        #[arg(
+            long = "param",
+            value_name = "NAME=VALUE",
+            value_parser = Params::parse_assignment,
+            conflicts_with = "all"
+        )]
+        params: Vec<(String, String)>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Draw how each part gets solved, for the days that can
+        #[arg(long, conflicts_with_all = ["all", "format"])]
+        render: bool,
+        /// Save that drawing as a PNG or PPM image, one per part when solving
+        /// both
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["all", "format"])]
+        image: Option<PathBuf>,
+        /// Record the simulation behind each part, as an animated GIF when the
+        /// path ends in `.gif`, otherwise as a directory of numbered PNG frames
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["all", "format"])]
+        animate: Option<PathBuf>,
+        /// Only record the steps FIRST..LAST, both included
+        #[arg(
+            long,
+            value_name = "FIRST..LAST",
+            value_parser = Frames::parse_range,
+            requires = "animate"
+        )]
+        frames: Option<(usize, Option<usize>)>,
+        /// Only record one step out of this many
+        #[arg(
+            long,
+            default_value_t = 1,
+            value_parser = clap::value_parser!(u64).range(1..),
+            requires = "animate"
+        )]
+        every: u64,
+        /// Milliseconds each frame of a GIF shows for
+        #[arg(long, default_value_t = 100, requires = "animate")]
+        delay: u64,
+        /// Width in pixels of each cell of images and animations
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u8).range(1..))]
+        scale: u8,
+    },
+    /// List the puzzle parameters of one or every day
+    Params {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+    },
+    /// Make up a random puzzle input for a day
+    Gen {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Seed of the input, so that it can be made again; random by default
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Rough size of the input, such as the side of a map or the length
+        /// of a list
+        #[arg(long, default_value_t = 10)]
+        size: usize,
+        /// Write the input there, with a `.toml` sidecar for the parameters it
+        /// needs, instead of printing it
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Re-solve every input of the answer manifest and compare the answers
+    Verify {
+        #[arg(long, default_value = "answers.toml")]
+        manifest: PathBuf,
+    },
+    /// Time parsing and each part of one or every day
+    Bench {
+        /// Only benchmark the given day
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+        /// Timed runs of each step
+        #[arg(long, default_value_t = 10)]
+        samples: usize,
+        /// Milliseconds to run each step before timing it
+        #[arg(long, default_value_t = 500)]
+        warm_up: u64,
+        /// Save the timings under this name
+        #[arg(long)]
+        save_baseline: Option<String>,
+        /// Compare the timings against a saved baseline
+        #[arg(long)]
+        baseline: Option<String>,
+    },
+    /// Start the solution of a day: its module, registered with the runner
+    /// and the benchmarks, and empty puzzle and example inputs
+    New {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+    /// Answer puzzles over HTTP: `POST /day/{n}/part/{p}` with the input as
+    /// the body and parameters in the query string, and `GET /days`
+    #[cfg(feature = "serve")]
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8024")]
+        address: String,
+        /// Largest input accepted, in bytes
+        #[arg(long, default_value_t = 1 << 20)]
+        max_body: usize,
+        /// Milliseconds a solver may run before its request fails
+        #[arg(long, default_value_t = 10_000)]
+        timeout: u64,
+        /// Solvers that may run at once; further requests are refused
+        #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u64).range(1..))]
+        solvers: u64,
+    },
+}
+
+/// A puzzle input, read once, with the parameters it is solved with
+struct Loaded {
+    input: Input,
+    text: String,
+    params: Params,
+}
+
+impl Loaded {
+    /// Reads `input` and its sidecar, with `overrides` on top
+    fn load(input: Input, overrides: &Params) -> Result<Loaded> {
+        let text = input.read()?;
+        let params = input.params()?.merged(overrides);
+        Ok(Loaded {
+            input,
+            text,
+            params,
+        })
+    }
+}
+
+/// Solves the loaded input, reporting a panicking solver as an error so that
+/// every part still gets a record
+fn run(day: u8, parts: &[Part], loaded: &Loaded) -> Result<Solved> {
+    catch_unwind(|| DAYS[day as usize - 1].solve_timed(&loaded.text, parts, &loaded.params))
+        .unwrap_or_else(|payload| Err(Error::panicked(payload)))
+        .map_err(|error| error.in_file(loaded.input.path()))
+}
+
+/// Like [`run`] on the day's own input
+fn run_default(day: u8, parts: &[Part]) -> Result<Solved> {
+    run(
+        day,
+        parts,
+        &Loaded::load(Input::of_day(day), &Params::default())?,
+    )
+}
+
+/// Solves every day on its default input, returning whether all succeeded
+fn run_all(parts: &[Part], format: Format) -> bool {
+    // Panics are reported in the table, not as they happen
+    panic::set_hook(Box::new(|_| {}));
+    let start = Instant::now();
+    let results: Vec<(u8, PathBuf, Result<Solved>)> = (1..=25u8)
+        .into_par_iter()
+        .map(|day| {
+            let input = Input::of_day(day).path().to_owned();
+            (day, input, run_default(day, parts))
+        })
+        .collect();
+    let elapsed = start.elapsed();
+    let _ = panic::take_hook();
+
+    match format {
+        Format::Text => print_table(&results, elapsed),
+        Format::Json => {
+            for (day, input, solved) in &results {
+                for record in Record::of_run(*day, input, parts, solved) {
+                    println!("{}", record.to_json());
+                }
+            }
+        }
+    }
+    results.iter().all(|(_, _, solved)| solved.is_ok())
+}
+
+fn print_table(results: &[(u8, PathBuf, Result<Solved>)], elapsed: Duration) {
+    let answer = |solved: &Solved, part: Part| {
+        solved
+            .parts
+            .iter()
+            .find(|(p, _, _)| *p == part)
+            .map_or(String::new(), |(_, answer, _)| match answer {
+                Answer::None => "-".to_owned(),
+                answer => answer.to_string(),
+            })
+    };
+    let time = |solved: &Solved, part: Part| {
+        solved
+            .parts
+            .iter()
+            .find(|(p, _, _)| *p == part)
+            .map_or(String::new(), |(_, _, elapsed)| format!("{elapsed:.2?}"))
+    };
+    let width = |part: Part| {
+        results
+            .iter()
+            .filter_map(|(_, _, solved)| solved.as_ref().ok())
+            .map(|solved| answer(solved, part).len())
+            .chain(["part 1".len()])
+            .max()
+            .unwrap()
+    };
+    let (width_1, width_2) = (width(Part::One), width(Part::Two));
+
+    println!(
+        "day  {:<width_1$}  {:<width_2$}  {:>9}  {:>9}  {:>9}",
+        "part 1", "part 2", "parse", "part 1", "part 2"
+    );
+    let mut failed = 0;
+    for (day, _, solved) in results {
+        match solved {
+            Ok(solved) => println!(
+                "{day:>3}  {:<width_1$}  {:<width_2$}  {:>9}  {:>9}  {:>9}",
+                answer(solved, Part::One),
+                answer(solved, Part::Two),
+                format!("{:.2?}", solved.parse),
+                time(solved, Part::One),
+                time(solved, Part::Two),
+            ),
+            Err(error) => {
+                failed += 1;
+                println!("{day:>3}  error: {error}");
+            }
+        }
+    }
+    println!(
+        "{} solved, {failed} failed in {elapsed:.2?}",
+        results.len() - failed
+    );
+}
+
+/// The picture of each part, for the parts the day can draw
+fn draw(day: u8, parts: &[Part], loaded: &Loaded) -> Result<Vec<(Part, Canvas)>> {
+    let mut canvases = Vec::new();
+    for &part in parts {
+        let canvas = DAYS[day as usize - 1]
+            .render(&loaded.text, part, &loaded.params)
+            .map_err(|error| error.in_file(loaded.input.path()))?;
+        canvases.extend(canvas.map(|canvas| (part, canvas)));
+    }
+    Ok(canvases)
+}
+
+/// Records the animation of each part with the recorder `recording` makes
+/// for its path, returning whether the day animated any
+fn animate(
+    day: u8,
+    parts: &[Part],
+    loaded: &Loaded,
+    path: &Path,
+    recording: impl Fn(&Path) -> Recorder,
+) -> Result<bool> {
+    let mut animated = false;
+    for &part in parts {
+        let path = part_path(path, part, parts);
+        let mut recorder = recording(&path);
+        if DAYS[day as usize - 1]
+            .animate(&loaded.text, part, &loaded.params, &mut recorder)
+            .map_err(|error| error.in_file(loaded.input.path()))?
+        {
+            let frames = recorder.finish()?;
+            println!(
+                "Saved {frames} frames of part {} to {}",
+                part.number(),
+                path.display()
+            );
+            animated = true;
+        }
+    }
+    Ok(animated)
+}
+
+/// Where the output of `part` goes: `path` itself when drawing a single part,
+/// otherwise `path` with the part number before its extension
+fn part_path(path: &Path, part: Part, parts: &[Part]) -> PathBuf {
+    match parts {
+        [_] => path.to_owned(),
+        _ => {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let name = match path.extension() {
+                Some(extension) => format!(
+                    "{stem}.part{}.{}",
+                    part.number(),
+                    extension.to_string_lossy()
+                ),
+                None => format!("{stem}.part{}", part.number()),
+            };
+            path.with_file_name(name)
+        }
+    }
+}
+
+fn print_answers(day: u8, solved: &Solved) {
+    for (part, answer, _) in &solved.parts {
+        match (part, answer) {
+            (Part::One, answer) => println!("First part answer: {answer}"),
+            // The last day is the only one without a second puzzle
+            (Part::Two, Answer::None) if day == 25 => println!("Day {day} has no second part"),
+            (Part::Two, answer) => println!("Second part answer: {answer}"),
+        }
+    }
+}
+
+fn list_params(days: &[u8]) {
+    for &day in days {
+        for param in DAYS[day as usize - 1].params() {
+            println!(
+                "day {day:>2}  {:<14} {:<16} {}",
+                param.name, param.default, param.about
+            );
+        }
+    }
+}
+
+fn generate(day: u8, seed: Option<u64>, size: usize, output: Option<&Path>) -> Result<()> {
+    let seed = seed.unwrap_or_else(|| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        now.as_nanos() as u64
+    });
+    let generated = DAYS[day as usize - 1].generate(seed, size);
+    let Some(path) = output else {
+        print!("{}", generated.input);
+        eprintln!("seed {seed}");
+        if !generated.params.is_empty() {
+            let params = generated
+                .params
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"));
+            eprintln!(
+                "solve with --param {}",
+                params.collect::<Vec<_>>().join(" --param ")
+            );
+        }
+        return Ok(());
+    };
+    write(path, &generated.input).map_err(|source| Error::Io {
+        path: path.to_owned(),
+        source,
+    })?;
+    println!("Wrote {} from seed {seed}", path.display());
+    if !generated.params.is_empty() {
+        generated.params.save_sidecar(path)?;
+        println!("Wrote {}", Params::sidecar_path(path).display());
+    }
+    Ok(())
+}
+
+/// Returns whether every listed answer still matches
+fn verify(manifest: &Path) -> Result<bool> {
+    let manifest = Manifest::load(manifest)?;
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+
+    for check in manifest.verify() {
+        let label = format!(
+            "day {:>2} part {} ({})",
+            check.entry.day,
+            check.part.number(),
+            check.entry.input.display()
+        );
+        let elapsed = format!("{:.2?}", check.elapsed);
+        match check.status {
+            Status::Pass => {
+                passed += 1;
+                println!("pass     {label} in {elapsed}");
+            }
+            Status::Fail { expected, actual } => {
+                failed += 1;
+                println!("FAIL     {label} in {elapsed}: expected {expected}, got {actual}");
+            }
+            Status::Missing { actual } => {
+                missing += 1;
+                println!("missing  {label} in {elapsed}: got {actual}");
+            }
+            Status::Error(error) => {
+                failed += 1;
+                println!("ERROR    {label}: {error}");
+            }
+        }
+    }
+    for day in manifest.unlisted_days() {
+        missing += 1;
+        println!("missing  day {day:>2}, no input listed");
+    }
+
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    Ok(failed == 0)
+}
+
+fn bench(
+    days: &[u8],
+    sampling: &Sampling,
+    save_baseline: Option<&str>,
+    baseline: Option<&str>,
+) -> Result<()> {
+    let baseline = baseline
+        .map(|name| Baseline::load(&Baseline::path(name)))
+        .transpose()?;
+    let mut measurements = Baseline::default();
+
+    for &day in days {
+        let loaded = match Loaded::load(Input::of_day(day), &Params::default()) {
+            Ok(loaded) => loaded,
+            Err(error) if days.len() > 1 => {
+                println!("day {day:>2}  skipped, {error}");
+                continue;
+            }
+            Err(error) => return Err(error),
+        };
+        let measurement = DAYS[day as usize - 1]
+            .bench(&loaded.text, &loaded.params, sampling)
+            .map_err(|error| error.in_file(loaded.input.path()))?;
+
+        let before = baseline.as_ref().and_then(|baseline| baseline.get(day));
+        let step = |name: &str, stats: &Stats, before: Option<&Stats>| match before
+            .and_then(|before| stats.change(before))
+        {
+            Some(change) => format!("{name} {:>9.2?} ({change:+.1}%)", stats.median()),
+            None => format!("{name} {:>9.2?}", stats.median()),
+        };
+        println!(
+            "day {day:>2}  {}  {}  {}",
+            step("parse", &measurement.parse, before.map(|m| &m.parse)),
+            step("part 1", &measurement.part_1, before.map(|m| &m.part_1)),
+            step("part 2", &measurement.part_2, before.map(|m| &m.part_2)),
+        );
+        measurements.insert(day, measurement);
+    }
+
+    if let Some(name) = save_baseline {
+        let path = Baseline::path(name);
+        measurements.save(&path)?;
+        println!("Saved baseline to {}", path.display());
+    }
+    Ok(())
+}
+
+/// Logs at the level `-v` asks for, unless RUST_LOG says otherwise
+fn init_logging(verbose: u8) {
+    let level = match verbose {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    env_logger::Builder::new()
+        .filter_level(level)
+        .parse_default_env()
+        .init();
+}
+
+fn main() {
+    let cli = Cli::parse();
+    init_logging(cli.verbose);
+    match cli.command {
+        Command::Run {
+            day,
+            all,
+            part,
+            input,
+            params,
+            format,
+            render,
+            image,
+            animate: animation,
+            frames,
+            every,
+            delay,
+            scale,
+        } => {
+            let parts = match part {
+                Some(1) => vec![Part::One],
+                Some(_) => vec![Part::Two],
+                None => Part::BOTH.to_vec(),
+            };
+            let day = match day {
+                Some(day) if !all => day,
+                _ => {
+                    if !run_all(&parts, format) {
+                        exit(1);
+                    }
+                    return;
+                }
+            };
+            let input = Input::resolve(day, input.as_deref());
+            let path = input.path().to_owned();
+            let overrides = params
+                .into_iter()
+                .fold(Params::default(), |mut all, (name, value)| {
+                    all.set(name, value);
+                    all
+                });
+            let solved = Loaded::load(input, &overrides)
+                .and_then(|loaded| run(day, &parts, &loaded).map(|solved| (loaded, solved)));
+            match format {
+                Format::Text => {
+                    let drawn = solved.and_then(|(loaded, solved)| {
+                        print_answers(day, &solved);
+                        let mut drawn = true;
+                        if render || image.is_some() {
+                            let canvases = draw(day, &parts, &loaded)?;
+                            for (part, canvas) in &canvases {
+                                if render {
+                                    let text = canvas.render(Style::detect());
+                                    println!("\nPart {}:\n{text}", part.number());
+                                }
+                                if let Some(path) = &image {
+                                    let path = part_path(path, *part, &parts);
+                                    canvas.to_image(scale as usize).save(&path)?;
+                                    println!("Saved part {} to {}", part.number(), path.display());
+                                }
+                            }
+                            drawn &= !canvases.is_empty();
+                        }
+                        if let Some(path) = &animation {
+                            let (first, last) = frames.unwrap_or((0, None));
+                            let frames = Frames {
+                                first,
+                                last,
+                                every: every as usize,
+                            };
+                            let recording =
+                                |path: &Path| Recorder::new(path, frames, scale as usize, delay);
+                            drawn &= animate(day, &parts, &loaded, path, recording)?;
+                        }
+                        Ok(drawn)
+                    });
+                    match drawn {
+                        Ok(true) => {}
+                        Ok(false) => println!("Day {day} has nothing to draw"),
+                        Err(error) => {
+                            eprintln!("error: {error}");
+                            exit(1);
+                        }
+                    }
+                }
+                Format::Json => {
+                    let solved = solved.map(|(_, solved)| solved);
+                    let records = Record::of_run(day, &path, &parts, &solved);
+                    for record in &records {
+                        println!("{}", record.to_json());
+                    }
+                    if solved.is_err() {
+                        exit(1);
+                    }
+                }
+            }
+        }
+        Command::Params { day } => match day {
+            Some(day) => list_params(&[day]),
+            None => list_params(&(1..=25).collect::<Vec<_>>()),
+        },
+        Command::Gen {
+            day,
+            seed,
+            size,
+            output,
+        } => {
+            if let Err(error) = generate(day, seed, size, output.as_deref()) {
+                eprintln!("error: {error}");
+                exit(1);
+            }
+        }
+        Command::Verify { manifest } => match verify(&manifest) {
+            Ok(true) => {}
+            Ok(false) => exit(1),
+            Err(error) => {
+                eprintln!("error: {error}");
+                exit(1);
+            }
+        },
+        Command::Bench {
+            day,
+            samples,
+            warm_up,
+            save_baseline,
+            baseline,
+        } => {
+            let days: Vec<u8> = match day {
+                Some(day) => vec![day],
+                None => (1..=25).collect(),
+            };
+            let sampling = Sampling {
+                warm_up: Duration::from_millis(warm_up),
+                samples,
+            };
+            if let Err(error) = bench(
+                &days,
+                &sampling,
+                save_baseline.as_deref(),
+                baseline.as_deref(),
+            ) {
+                eprintln!("error: {error}");
+                exit(1);
+            }
+        }
+        Command::New { day } => match scaffold::new_day(Path::new("."), day) {
+            Ok(written) => {
+                for path in written {
+                    println!("Wrote {}", path.display());
+                }
+            }
+            Err(error) => {
+                eprintln!("error: {error}");
+                exit(1);
+            }
+        },
+        #[cfg(feature = "serve")]
+        Command::Serve {
+            address,
+            max_body,
+            timeout,
+            solvers,
+        } => {
+            let limits = Limits {
+                max_body,
+                timeout: Duration::from_millis(timeout),
+                solvers: solvers as usize,
+            };
+            match Service::bind(&address, limits) {
+                Ok(service) => {
+                    eprintln!("Serving on http://{address}");
+                    service.run();
+                }
+                Err(error) => {
+                    eprintln!("error: {error}");
+                    exit(1);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    fs::read_to_string,
+    panic::catch_unwind,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+
+use crate::{params::Params, Answer, Error, Part, Result, DAYS};
+
+/// Checked-in answers of the solved puzzles, one entry per input file
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    #[serde(default, rename = "answer")]
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Entry {
+    pub day: u8,
+    pub input: PathBuf,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+        actual: Answer,
+    },
+    /// Solved, but the manifest has no answer to compare against
+    Missing {
+        actual: Answer,
+    },
+    /// The input could not be read or parsed
+    Error(Error),
+}
+
+/// Outcome of re-solving one part of a manifest entry
+#[derive(Debug)]
+pub struct Check<'a> {
+    pub entry: &'a Entry,
+    pub part: Part,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        let manifest: Manifest = toml::from_str(&text).map_err(|e| Error::Invalid {
+            path: path.to_owned(),
+            reason: e.message().to_owned(),
+        })?;
+
+        match manifest
+            .entries
+            .iter()
+            .find(|entry| !(1..=25).contains(&entry.day))
+        {
+            Some(entry) => Err(Error::Invalid {
+                path: path.to_owned(),
+                reason: format!("day {} is not between 1 and 25", entry.day),
+            }),
+            None => Ok(manifest),
+        }
+    }
+
+    /// Days without any entry
+    pub fn unlisted_days(&self) -> Vec<u8> {
+        (1..=25)
+            .filter(|day| self.entries.iter().all(|entry| entry.day != *day))
+            .collect()
+    }
+
+    /// Re-solves both parts of every entry, in manifest order. A part with
+    /// neither an expected nor an actual answer, like the second part of the
+    /// last day, is left out.
+    pub fn verify(&self) -> Vec<Check<'_>> {
+        self.entries.iter().flat_map(Entry::verify).collect()
+    }
+}
+
+impl Entry {
+    pub fn expected(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part_1.as_deref(),
+            Part::Two => self.part_2.as_deref(),
+        }
+    }
+
+    fn verify(&self) -> Vec<Check<'_>> {
+        let buffer = match read_to_string(&self.input) {
+            Ok(buffer) => buffer,
+            Err(source) => {
+                let error = Error::Io {
+                    path: self.input.clone(),
+                    source,
+                };
+                return vec![self.check(Part::One, Status::Error(error), Duration::ZERO)];
+            }
+        };
+        let params = match Params::sidecar(&self.input) {
+            Ok(params) => params,
+            Err(error) => return vec![self.check(Part::One, Status::Error(error), Duration::ZERO)],
+        };
+
+        let mut checks = Vec::new();
+        for part in Part::BOTH {
+            let start = Instant::now();
+            // A panicking solver fails its entry, not the whole run
+            let answer =
+                catch_unwind(|| DAYS[self.day as usize - 1].solve(&buffer, &[part], &params))
+                    .unwrap_or_else(|payload| Err(Error::panicked(payload)));
+            let elapsed = start.elapsed();
+
+            let status = match answer {
+                // The other part may still get an answer
+                Err(error @ Error::Panicked { .. }) => Status::Error(error.in_file(&self.input)),
+                // Parsing failed, the other part would fail the same way
+                Err(error) => {
+                    let status = Status::Error(error.in_file(&self.input));
+                    checks.push(self.check(part, status, elapsed));
+                    break;
+                }
+                Ok(mut answers) => match (answers.remove(0), self.expected(part)) {
+                    (Answer::None, None) => continue,
+                    (actual, None) => Status::Missing { actual },
+                    (actual, Some(expected)) if actual.to_string() == expected => Status::Pass,
+                    (actual, Some(expected)) => Status::Fail {
+                        expected: expected.to_owned(),
+                        actual,
+                    },
+                },
+            };
+            checks.push(self.check(part, status, elapsed));
+        }
+        checks
+    }
+
+    fn check(&self, part: Part, status: Status, elapsed: Duration) -> Check<'_> {
+        Check {
+            entry: self,
+            part,
+            status,
+            elapsed,
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::{collections::HashMap, fmt::Display, hash::Hash};
+
+/// Results of a recursive function, remembered for the length of one solve.
+/// The key must hold everything the result depends on that is not fixed for
+/// the whole solve, and a new memo is made for each input, so nothing is
+/// shared between inputs solved in the same process.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    values: HashMap<K, V>,
+    stats: Stats,
+}
+
+/// How often a memo knew the result asked for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl Stats {
+    /// The share of lookups that found a result, between 0 and 1
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            100.0 * self.hit_rate()
+        )
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo {
+            values: HashMap::new(),
+            stats: Stats::default(),
+        }
+    }
+
+    /// The result remembered for `key`, counted as a hit or a miss
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.values.get(key).cloned();
+        match value {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        value
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.values.insert(key, value);
+    }
+
+    /// The result for `key`, computed by `compute` the first time it is
+    /// asked for. `compute` is given the memo back, so it can recurse.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = compute(self);
+        self.insert(key, value.clone());
+        value
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Number of results remembered
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Forgets every result and resets the statistics
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.stats = Stats::default();
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        match n {
+            0 | 1 => n,
+            _ => memo.get_or_compute(n, |memo| fibonacci(n - 1, memo) + fibonacci(n - 2, memo)),
+        }
+    }
+
+    #[test]
+    fn remembers_results() {
+        let mut memo = Memo::new();
+        assert_eq!(fibonacci(50, &mut memo), 12586269025);
+        assert_eq!(memo.len(), 49);
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 47,
+                misses: 49
+            }
+        );
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats(), Stats::default());
+    }
+}
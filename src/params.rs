@@ -0,0 +1,270 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs::{read_to_string, write},
+    io::ErrorKind,
+    ops::{Bound, RangeBounds},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::Serialize;
+
+use crate::{Error, Result};
+
+/// A puzzle constant that is not part of the input, such as the size of a
+/// map, which custom inputs may need to change
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: &'static str,
+    pub about: &'static str,
+}
+
+impl Param {
+    pub const fn new(name: &'static str, default: &'static str, about: &'static str) -> Self {
+        Param {
+            name,
+            default,
+            about,
+        }
+    }
+}
+
+/// Values given to the parameters of a day, by name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    values: BTreeMap<String, String>,
+}
+
+impl Params {
+    /// Reads a `name=value` assignment, as given on the command line
+    pub fn parse_assignment(assignment: &str) -> std::result::Result<(String, String), String> {
+        match assignment.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_owned(), value.trim().to_owned()))
+            }
+            _ => Err(format!("expected `name=value`, got `{assignment}`")),
+        }
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(name.into(), value.into());
+    }
+
+    /// Where the parameters of an input file are kept: next to it, with a
+    /// `.toml` extension
+    pub fn sidecar_path(input: &Path) -> PathBuf {
+        input.with_extension("toml")
+    }
+
+    /// Loads the sidecar of `input`, or no parameters if it has none
+    pub fn sidecar(input: &Path) -> Result<Params> {
+        let path = Params::sidecar_path(input);
+        let text = match read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Params::default()),
+            Err(source) => return Err(Error::Io { path, source }),
+        };
+        let invalid = |reason: String| Error::Invalid {
+            path: path.clone(),
+            reason,
+        };
+        let table: toml::Table =
+            toml::from_str(&text).map_err(|e| invalid(e.message().to_owned()))?;
+
+        let mut params = Params::default();
+        for (name, value) in table {
+            let value = match value {
+                toml::Value::String(text) => text,
+                toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => {
+                    value.to_string()
+                }
+                _ => return Err(invalid(format!("`{name}` is not a single value"))),
+            };
+            params.set(name, value);
+        }
+        Ok(params)
+    }
+
+    /// Saves these parameters as the sidecar of `input`
+    pub fn save_sidecar(&self, input: &Path) -> Result<()> {
+        let table: toml::Table = self
+            .values
+            .iter()
+            .map(|(name, value)| {
+                let value = match value.parse() {
+                    Ok(number) => toml::Value::Integer(number),
+                    Err(_) => toml::Value::String(value.clone()),
+                };
+                (name.clone(), value)
+            })
+            .collect();
+        let path = Params::sidecar_path(input);
+        write(&path, table.to_string()).map_err(|source| Error::Io { path, source })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Every value, by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// These parameters, overridden by the ones of `other`
+    pub fn merged(mut self, other: &Params) -> Params {
+        self.values.extend(other.values.clone());
+        self
+    }
+
+    /// Checks every value against the `declared` parameters of a day, and
+    /// fills in the defaults of the missing ones
+    pub fn resolve(&self, declared: &[Param]) -> Result<Params> {
+        if let Some(name) = self
+            .values
+            .keys()
+            .find(|name| !declared.iter().any(|param| param.name == *name))
+        {
+            let known: Vec<&str> = declared.iter().map(|param| param.name).collect();
+            return Err(Error::BadParam {
+                name: name.clone(),
+                reason: match known.is_empty() {
+                    true => "the day takes no parameters".to_owned(),
+                    false => format!("expected one of {}", known.join(", ")),
+                },
+            });
+        }
+
+        let mut resolved = Params::defaults(declared);
+        resolved.values.extend(self.values.clone());
+        Ok(resolved)
+    }
+
+    /// The default value of every `declared` parameter
+    pub fn defaults(declared: &[Param]) -> Params {
+        let mut params = Params::default();
+        for param in declared {
+            params.set(param.name, param.default);
+        }
+        params
+    }
+
+    /// The value of a parameter, which must have been declared
+    pub fn get<T>(&self, name: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self
+            .values
+            .get(name)
+            .unwrap_or_else(|| panic!("parameter `{name}` was never declared"));
+        value.parse().map_err(|e: T::Err| Error::BadParam {
+            name: name.to_owned(),
+            reason: format!("`{value}` is invalid: {e}"),
+        })
+    }
+
+    /// Like [`Params::get`], for a value that only makes sense within
+    /// `range`, which usually depends on the input
+    pub fn get_within<T>(&self, name: &str, range: impl RangeBounds<T>) -> Result<T>
+    where
+        T: FromStr + PartialOrd + Display,
+        T::Err: Display,
+    {
+        let value = self.get(name)?;
+        if range.contains(&value) {
+            return Ok(value);
+        }
+        let expected = match (range.start_bound(), range.end_bound()) {
+            (Bound::Included(start), Bound::Included(end)) => format!("from {start} to {end}"),
+            (start, end) => {
+                let start = match start {
+                    Bound::Included(start) => Some(format!("at least {start}")),
+                    Bound::Excluded(start) => Some(format!("more than {start}")),
+                    Bound::Unbounded => None,
+                };
+                let end = match end {
+                    Bound::Included(end) => Some(format!("at most {end}")),
+                    Bound::Excluded(end) => Some(format!("less than {end}")),
+                    Bound::Unbounded => None,
+                };
+                start
+                    .into_iter()
+                    .chain(end)
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            }
+        };
+        Err(Error::BadParam {
+            name: name.to_owned(),
+            reason: format!("`{value}` is out of range, expected {expected}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECLARED: &[Param] = &[
+        Param::new("width", "71", "width of the map"),
+        Param::new("label", "z45", "name of a wire"),
+    ];
+
+    #[test]
+    fn overrides_and_defaults() {
+        let mut params = Params::default();
+        params.set("width", "7");
+        let resolved = params.resolve(DECLARED).unwrap();
+        assert_eq!(resolved.get::<usize>("width").unwrap(), 7);
+        assert_eq!(resolved.get::<String>("label").unwrap(), "z45");
+
+        let defaults = Params::default().resolve(DECLARED).unwrap();
+        assert_eq!(defaults.get::<usize>("width").unwrap(), 71);
+    }
+
+    #[test]
+    fn bad_parameters() {
+        let mut params = Params::default();
+        params.set("height", "7");
+        let error = params.resolve(DECLARED).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "bad parameter `height`: expected one of width, label"
+        );
+
+        let mut params = Params::default();
+        params.set("width", "wide");
+        let resolved = params.resolve(DECLARED).unwrap();
+        assert!(resolved.get::<usize>("width").is_err());
+
+        params.set("width", "3");
+        let resolved = params.resolve(DECLARED).unwrap();
+        assert_eq!(resolved.get_within::<usize>("width", 1..=5).unwrap(), 3);
+        for (range, error) in [
+            (
+                (Bound::Included(7), Bound::Included(71)),
+                "bad parameter `width`: `3` is out of range, expected from 7 to 71",
+            ),
+            (
+                (Bound::Included(7), Bound::Unbounded),
+                "bad parameter `width`: `3` is out of range, expected at least 7",
+            ),
+            (
+                (Bound::Excluded(3), Bound::Excluded(9)),
+                "bad parameter `width`: `3` is out of range, expected more than 3 and less than 9",
+            ),
+        ] {
+            let refused = resolved.get_within::<usize>("width", range).unwrap_err();
+            assert_eq!(refused.to_string(), error);
+        }
+
+        assert!(Params::parse_assignment("width=7").is_ok());
+        assert!(Params::parse_assignment("width").is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use std::{fmt::Display, str::FromStr};
+
+use regex::Regex;
+
+use crate::{
+    error::{Error, Location, Result},
+    grid::Grid,
+    point::Point,
+};
+
+/// The text of a puzzle input. Any slice borrowed from it can be traced back
+/// to its line and column, which is what the error helpers below report.
+///
+/// A source can also stand for one section of the input: it then reads only
+/// that section, while locations still count from the start of the input and
+/// name the section they are in.
+#[derive(Clone, Copy)]
+pub struct Source<'a> {
+    input: &'a str,
+    text: &'a str,
+    section: Option<&'a str>,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Source {
+            input: text,
+            text,
+            section: None,
+        }
+    }
+
+    /// The text this source reads, be it the whole input or a section
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Location of `slice` in the input, or of the end of the input if the
+    /// slice was not borrowed from it
+    pub fn location(&self, slice: &str) -> Location {
+        let start = self.input.as_ptr() as usize;
+        let offset = (slice.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|&offset| offset <= self.input.len() && self.input.is_char_boundary(offset))
+            .unwrap_or(self.input.len());
+
+        let before = &self.input[..offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Location {
+            file: None,
+            section: self.section.map(str::to_owned),
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// The lines of the text that are not blank, without their line endings,
+    /// be they `\n` or `\r\n`
+    pub fn lines(&self) -> impl Iterator<Item = &'a str> {
+        self.text
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.trim().is_empty())
+    }
+
+    /// The blocks of lines of the text, separated by blank lines
+    pub fn sections(&self) -> impl Iterator<Item = Source<'a>> {
+        let source = *self;
+        let mut lines = self.text.lines().peekable();
+        std::iter::from_fn(move || {
+            while lines.next_if(|line| line.trim().is_empty()).is_some() {}
+            let first = lines.next()?;
+            let mut last = first;
+            while let Some(line) = lines.next_if(|line| !line.trim().is_empty()) {
+                last = line;
+            }
+            let start = first.as_ptr() as usize - source.text.as_ptr() as usize;
+            let end = last.as_ptr() as usize + last.len() - source.text.as_ptr() as usize;
+            Some(Source {
+                text: &source.text[start..end],
+                ..source
+            })
+        })
+    }
+
+    /// Splits the text into exactly `N` sections, named after what they hold
+    /// so that errors can tell which one they come from
+    pub fn split_sections<const N: usize>(&self, names: [&'a str; N]) -> Result<[Source<'a>; N]> {
+        let mut sections = self.sections();
+        let mut named = Vec::with_capacity(N);
+        for name in names {
+            let section = sections.next().ok_or_else(|| self.missing(name))?;
+            named.push(Source {
+                section: Some(name),
+                ..section
+            });
+        }
+        if let Some(extra) = sections.next() {
+            let line = extra.lines().next().unwrap();
+            return Err(self.unexpected(line, "the end of the input"));
+        }
+        Ok(named.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+
+    pub fn number<T>(&self, token: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        token.parse::<T>().map_err(|e| Error::BadNumber {
+            location: self.location(token),
+            token: token.to_owned(),
+            reason: e.to_string(),
+        })
+    }
+
+    pub fn unexpected(&self, token: &str, expected: &str) -> Error {
+        Error::UnexpectedToken {
+            location: self.location(token),
+            token: token.to_owned(),
+            expected: expected.to_owned(),
+        }
+    }
+
+    /// Error for a section the text ended without
+    pub fn missing(&self, section: &str) -> Error {
+        Error::MissingSection {
+            location: self.location(&self.text[self.text.len()..]),
+            section: section.to_owned(),
+        }
+    }
+
+    /// Splits `text` on the first `separator`, failing if there is none
+    pub fn split_once(&self, text: &'a str, separator: &str) -> Result<(&'a str, &'a str)> {
+        text.split_once(separator)
+            .ok_or_else(|| self.unexpected(text, &format!("`{separator}`")))
+    }
+
+    /// The rest of `text` after `prefix`, failing if it does not start with it
+    pub fn strip_prefix(&self, text: &'a str, prefix: &str) -> Result<&'a str> {
+        text.strip_prefix(prefix)
+            .ok_or_else(|| self.unexpected(text, &format!("`{prefix}`")))
+    }
+
+    /// Reads every number of `text`, which any of the `separators` split
+    pub fn numbers<T>(&self, text: &'a str, separators: &[char]) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        text.split(separators)
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(|token| self.number(token))
+            .collect()
+    }
+
+    /// Matches `text` against a `template` such as `"Button A: X+{}, Y+{}"`,
+    /// reading each of its `{}` fields as a `T`
+    pub fn fields<T, const N: usize>(&self, template: &str, text: &'a str) -> Result<[T; N]>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let mut literals = template.split("{}");
+        assert_eq!(
+            literals.clone().count(),
+            N + 1,
+            "`{template}` needs {N} fields"
+        );
+        let mismatch = || self.unexpected(text, &format!("`{template}`"));
+
+        let mut rest = text
+            .strip_prefix(literals.next().unwrap())
+            .ok_or_else(mismatch)?;
+        let mut fields = Vec::with_capacity(N);
+        for literal in literals {
+            let end = match literal.is_empty() {
+                true => rest.len(),
+                false => rest.find(literal).ok_or_else(mismatch)?,
+            };
+            fields.push(self.number(&rest[..end])?);
+            rest = &rest[end + literal.len()..];
+        }
+        if !rest.is_empty() {
+            return Err(mismatch());
+        }
+        Ok(fields.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+
+    /// Matches the whole of `text` against `pattern`, returning its capture groups
+    pub fn captures<const N: usize>(
+        &self,
+        pattern: &Regex,
+        text: &'a str,
+        expected: &str,
+    ) -> Result<[&'a str; N]> {
+        match pattern.captures(text) {
+            Some(captures) if captures.get(0).unwrap().range() == (0..text.len()) => {
+                Ok(captures.extract().1)
+            }
+            _ => Err(self.unexpected(text, expected)),
+        }
+    }
+
+    /// Position of the only `tile` of `text`, read as a grid. `name` is what
+    /// the tile stands for, such as `guard`, for the errors when the grid has
+    /// none or more than one.
+    pub fn tile(&self, text: &'a str, tile: char, name: &str) -> Result<Point> {
+        let mut found = None;
+        for (y, row) in text.trim_matches(['\r', '\n']).lines().enumerate() {
+            for (x, (offset, c)) in row.char_indices().enumerate() {
+                if c != tile {
+                    continue;
+                }
+                if found.is_some() {
+                    let token = &row[offset..offset + c.len_utf8()];
+                    return Err(self.unexpected(token, &format!("a single {name}")));
+                }
+                found = Some(Point::new(x as i32, y as i32));
+            }
+        }
+        found.ok_or_else(|| self.missing(&format!("the {name} `{tile}`")))
+    }
+
+    /// Reads `text` as a grid of characters
+    pub fn grid(&self, text: &'a str) -> Result<Grid<char>> {
+        self.grid_with(text, "a tile", Some)
+    }
+
+    /// Reads `text` as a grid, one cell per character. Characters that `cell`
+    /// rejects are reported as not being what was `expected`.
+    pub fn grid_with<T>(
+        &self,
+        text: &'a str,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Grid<T>> {
+        let mut rows = text.trim_matches(['\r', '\n']).lines().peekable();
+        let width = match rows.peek() {
+            Some(row) if !row.is_empty() => row.chars().count(),
+            _ => return Err(self.missing("a grid")),
+        };
+
+        let mut cells = Vec::new();
+        for row in rows {
+            if row.chars().count() != width {
+                return Err(self.unexpected(row, &format!("a row of {width} tiles")));
+            }
+            for (offset, c) in row.char_indices() {
+                cells.push(cell(c).ok_or_else(|| {
+                    self.unexpected(&row[offset..offset + c.len_utf8()], expected)
+                })?);
+            }
+        }
+        Ok(Grid::from_cells(width, cells))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_ignore_line_endings() {
+        let input = "a\r\nb\r\n\r\n\r\nc\r\n\r\nd\r";
+        let source = Source::new(input);
+        let sections: Vec<&str> = source.sections().map(|s| s.text()).collect();
+        assert_eq!(sections, ["a\r\nb", "c", "d\r"]);
+        let lines: Vec<&str> = source.sections().flat_map(|s| s.lines()).collect();
+        assert_eq!(lines, ["a", "b", "c", "d"]);
+
+        let [first, second, _] = source
+            .split_sections(["letters", "more letters", "last letter"])
+            .unwrap();
+        assert_eq!(first.text(), "a\r\nb");
+        let error = second.unexpected(second.text(), "a number");
+        assert_eq!(
+            error.to_string(),
+            "line 5, column 1 (in more letters): unexpected `c`, expected a number"
+        );
+
+        let Err(error) = source.split_sections(["one", "two", "three", "four"]) else {
+            panic!("the input has only three sections");
+        };
+        assert_eq!(error.to_string(), "line 7, column 3: missing four");
+        assert!(source.split_sections(["just one"]).is_err());
+    }
+
+    #[test]
+    fn templates() {
+        let line = "Button A: X+94, Y-34";
+        let source = Source::new(line);
+        assert_eq!(
+            source
+                .fields::<i32, 2>("Button A: X+{}, Y{}", line)
+                .unwrap(),
+            [94, -34]
+        );
+        assert!(source
+            .fields::<i32, 2>("Button B: X+{}, Y{}", line)
+            .is_err());
+        assert!(source.fields::<i32, 1>("Button A: X+{}", line).is_err());
+
+        let error = source
+            .fields::<u8, 2>("Button A: X+{}, Y{}", line)
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("line 1, column 18: bad number `-34`"));
+    }
+
+    #[test]
+    fn tiles() {
+        let map = "#..\n.S.\n..E\n";
+        let source = Source::new(map);
+        assert_eq!(source.tile(map, 'S', "start").unwrap(), Point::new(1, 1));
+        assert_eq!(
+            source.tile(map, '^', "guard").unwrap_err().to_string(),
+            "line 4, column 1: missing the guard `^`"
+        );
+        assert_eq!(
+            source.tile(map, '.', "floor").unwrap_err().to_string(),
+            "line 1, column 3: unexpected `.`, expected a single floor"
+        );
+    }
+
+    #[test]
+    fn number_lists() {
+        let line = "3, 4,5 ,  6";
+        let source = Source::new(line);
+        assert_eq!(source.numbers::<u32>(line, &[',']).unwrap(), [3, 4, 5, 6]);
+        assert_eq!(
+            source.numbers::<u32>("1 2\t3", &[' ', '\t']).unwrap(),
+            [1, 2, 3]
+        );
+        assert!(source.numbers::<u32>(line, &[' ']).is_err());
+    }
+}
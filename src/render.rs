@@ -0,0 +1,260 @@
+use std::{
+    collections::HashSet,
+    env,
+    io::{stdout, IsTerminal},
+};
+
+use crate::{
+    grid::Grid,
+    image::{Image, Rgb},
+    point::Point,
+};
+
+/// Colours an overlay can be drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    /// Every colour, for drawing many overlays that should tell apart
+    pub const PALETTE: [Color; 6] = [
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+    ];
+
+    /// The ANSI escape that switches the foreground to this colour
+    fn escape(self) -> &'static str {
+        match self {
+            Color::Red => "\x1b[1;31m",
+            Color::Green => "\x1b[1;32m",
+            Color::Yellow => "\x1b[1;33m",
+            Color::Blue => "\x1b[1;34m",
+            Color::Magenta => "\x1b[1;35m",
+            Color::Cyan => "\x1b[1;36m",
+        }
+    }
+
+    /// The colour of this overlay in images
+    pub fn rgb(self) -> Rgb {
+        match self {
+            Color::Red => Rgb(230, 60, 50),
+            Color::Green => Rgb(60, 200, 80),
+            Color::Yellow => Rgb(240, 210, 40),
+            Color::Blue => Rgb(60, 110, 230),
+            Color::Magenta => Rgb(210, 70, 200),
+            Color::Cyan => Rgb(50, 200, 220),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::Yellow => "yellow",
+            Color::Blue => "blue",
+            Color::Magenta => "magenta",
+            Color::Cyan => "cyan",
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// How a canvas is written out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Overlays in colour, with ANSI escapes
+    Ansi,
+    /// Characters only, so overlays show through their glyphs alone
+    Plain,
+}
+
+impl Style {
+    /// Colours when writing to a terminal, unless `NO_COLOR` is set
+    pub fn detect() -> Style {
+        match stdout().is_terminal() && env::var_os("NO_COLOR").is_none() {
+            true => Style::Ansi,
+            false => Style::Plain,
+        }
+    }
+}
+
+struct Overlay {
+    label: String,
+    color: Color,
+    glyph: Option<char>,
+    cells: HashSet<Point>,
+}
+
+/// A map of characters with coloured overlays drawn over it, each overlay on
+/// top of the ones added before
+pub struct Canvas {
+    base: Grid<char>,
+    overlays: Vec<Overlay>,
+}
+
+impl Canvas {
+    pub fn new(base: Grid<char>) -> Self {
+        Canvas {
+            base,
+            overlays: Vec::new(),
+        }
+    }
+
+    /// Colours `cells`, keeping the characters under them
+    pub fn highlight(
+        self,
+        label: &str,
+        color: Color,
+        cells: impl IntoIterator<Item = Point>,
+    ) -> Self {
+        self.overlay(label, color, None, cells)
+    }
+
+    /// Colours `cells` and draws `glyph` on each of them
+    pub fn mark(
+        self,
+        label: &str,
+        color: Color,
+        glyph: char,
+        cells: impl IntoIterator<Item = Point>,
+    ) -> Self {
+        self.overlay(label, color, Some(glyph), cells)
+    }
+
+    fn overlay(
+        mut self,
+        label: &str,
+        color: Color,
+        glyph: Option<char>,
+        cells: impl IntoIterator<Item = Point>,
+    ) -> Self {
+        self.overlays.push(Overlay {
+            label: label.to_owned(),
+            color,
+            glyph,
+            cells: cells.into_iter().collect(),
+        });
+        self
+    }
+
+    /// The topmost overlay drawn over `point`
+    fn overlay_at(&self, point: Point) -> Option<&Overlay> {
+        self.overlays
+            .iter()
+            .rev()
+            .find(|overlay| overlay.cells.contains(&point))
+    }
+
+    /// The map with its overlays, followed by a legend of what they show
+    pub fn render(&self, style: Style) -> String {
+        let mut text = String::new();
+        for (y, row) in self.base.rows().enumerate() {
+            let mut current = None;
+            for (x, &c) in row.iter().enumerate() {
+                let overlay = self.overlay_at(Point::new(x as i32, y as i32));
+                let color = overlay.map(|overlay| overlay.color);
+                if style == Style::Ansi && color != current {
+                    text.push_str(color.map_or(RESET, Color::escape));
+                    current = color;
+                }
+                text.push(overlay.and_then(|overlay| overlay.glyph).unwrap_or(c));
+            }
+            if current.is_some() {
+                text.push_str(RESET);
+            }
+            text.push('\n');
+        }
+
+        // Overlays sharing a label, such as the regions of a map, share a line
+        let mut labels = HashSet::new();
+        for overlay in &self.overlays {
+            if !labels.insert(&overlay.label) {
+                continue;
+            }
+            let sample = overlay.glyph.unwrap_or('■');
+            match style {
+                Style::Ansi => text.push_str(&format!(
+                    "{}{sample}{RESET} {}\n",
+                    overlay.color.escape(),
+                    overlay.label
+                )),
+                Style::Plain => text.push_str(&format!(
+                    "{sample} {} ({})\n",
+                    overlay.label,
+                    overlay.color.name()
+                )),
+            }
+        }
+        text
+    }
+
+    /// The map as a picture, `scale` pixels wide per cell. Overlays keep their
+    /// colour; under them, walls are grey and empty cells black.
+    pub fn to_image(&self, scale: usize) -> Image {
+        let cells = Grid::from_cells(self.base.width(), self.base.positions().collect::<Vec<_>>());
+        Image::from_grid(&cells, scale, |&point| match self.overlay_at(point) {
+            Some(overlay) => overlay.color.rgb(),
+            None => match self.base[point] {
+                '.' | ' ' => Rgb::BLACK,
+                '#' => Rgb(110, 110, 110),
+                _ => Rgb(200, 200, 200),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas() -> Canvas {
+        Canvas::new(Grid::from_cells(3, "#..#.#".chars().collect()))
+            .highlight("walls", Color::Blue, [Point::new(0, 0), Point::new(0, 1)])
+            .mark(
+                "path",
+                Color::Red,
+                'O',
+                [Point::new(1, 0), Point::new(2, 0)],
+            )
+    }
+
+    #[test]
+    fn plain_text_shows_glyphs() {
+        assert_eq!(
+            canvas().render(Style::Plain),
+            "#OO\n#.#\n■ walls (blue)\nO path (red)\n"
+        );
+    }
+
+    #[test]
+    fn ansi_colours_runs_of_cells() {
+        let text = canvas().render(Style::Ansi);
+        let first_row = text.lines().next().unwrap();
+        assert_eq!(first_row, "\x1b[1;34m#\x1b[1;31mOO\x1b[0m");
+        let second_row = text.lines().nth(1).unwrap();
+        assert_eq!(second_row, "\x1b[1;34m#\x1b[0m.#");
+    }
+
+    #[test]
+    fn images_colour_overlays() {
+        let image = canvas().to_image(1);
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        let pixels: Vec<&[u8]> = ppm[11..].chunks(3).collect();
+        assert_eq!(pixels[0], [60, 110, 230]);
+        assert_eq!(pixels[1], [230, 60, 50]);
+        assert_eq!(pixels[4], [0, 0, 0]);
+        assert_eq!(pixels[5], [110, 110, 110]);
+    }
+}
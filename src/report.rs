@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{Answer, Part, Result, Solved};
+
+/// Outcome of solving one part of a day, laid out for scripts rather than
+/// people. Durations are in nanoseconds.
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub input: PathBuf,
+    /// `None` when the run failed, or when the day has no such part
+    pub answer: Option<String>,
+    pub parse_ns: Option<u64>,
+    pub solve_ns: Option<u64>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl Record {
+    /// One record per part asked for. When the input could not be read or
+    /// parsed, every part fails with the same error.
+    pub fn of_run(day: u8, input: &Path, parts: &[Part], solved: &Result<Solved>) -> Vec<Record> {
+        let nanos = |duration: std::time::Duration| duration.as_nanos() as u64;
+        match solved {
+            Ok(solved) => solved
+                .parts
+                .iter()
+                .map(|(part, answer, elapsed)| Record {
+                    day,
+                    part: part.number(),
+                    input: input.to_owned(),
+                    answer: match answer {
+                        Answer::None => None,
+                        answer => Some(answer.to_string()),
+                    },
+                    parse_ns: Some(nanos(solved.parse)),
+                    solve_ns: Some(nanos(*elapsed)),
+                    success: true,
+                    error: None,
+                })
+                .collect(),
+            Err(error) => parts
+                .iter()
+                .map(|part| Record {
+                    day,
+                    part: part.number(),
+                    input: input.to_owned(),
+                    answer: None,
+                    parse_ns: None,
+                    solve_ns: None,
+                    success: false,
+                    error: Some(error.to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    /// The record as a single line of JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    fs::{read_to_string, write, OpenOptions},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use crate::{input::Input, Error, Result};
+
+/// The module of a new day, with `@DAY@` standing for its number. Both parts
+/// answer nothing until written, and the test of the example fails until
+/// given the example's answer.
+const MODULE: &str = r#"use crate::{
+    generate::{Generated, Generator},
+    parse::Source,
+    Answer, Result, Solution,
+};
+
+pub struct Day@DAY@;
+
+impl Solution for Day@DAY@ {
+    const DAY: u8 = @DAY@;
+
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_input(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer {
+        part_1(input).into()
+    }
+
+    fn part_2(input: &Self::Input) -> Answer {
+        part_2(input).into()
+    }
+
+    fn generate(generator: &mut Generator) -> Generated {
+        let _ = generator;
+        Generated::new(String::new())
+    }
+}
+
+fn part_1(input: &[String]) -> Option<usize> {
+    let _ = input;
+    None
+}
+
+fn part_2(input: &[String]) -> Option<usize> {
+    let _ = input;
+    None
+}
+
+fn read_input(input: &str) -> Result<Vec<String>> {
+    let source = Source::new(input);
+    Ok(source.lines().map(str::to_owned).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::read_example;
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(
+            part_1(&Day@DAY@::parse(&read_example(@DAY@, "test")).unwrap()),
+            Some(0)
+        );
+    }
+}
+"#;
+
+/// Starts the solution of a day in the crate at `root`: writes its module,
+/// registers it in `src/lib.rs` and the benchmarks, and makes empty puzzle
+/// and example inputs in the input folder. Returns the files written, in
+/// that order. Existing inputs are kept, but an existing module is an error.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let module = root.join(format!("src/day_{day}.rs"));
+    if module.exists() {
+        return Err(Error::Exists { path: module });
+    }
+    let lib = root.join("src/lib.rs");
+    let registered = register(&read(&lib)?, day).map_err(|reason| invalid(&lib, reason))?;
+    let benches = root.join("benches/days.rs");
+    let benched = bench(&read(&benches)?, day).map_err(|reason| invalid(&benches, reason))?;
+
+    save(&module, &MODULE.replace("@DAY@", &day.to_string()))?;
+    save(&lib, &registered)?;
+    save(&benches, &benched)?;
+    let mut written = vec![module, lib, benches];
+    for input in [Input::of_day(day), Input::variant(day, "test")] {
+        let Input::File(path) = input else {
+            unreachable!("inputs of days are files")
+        };
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => written.push(path),
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => {}
+            Err(source) => return Err(Error::Io { path, source }),
+        }
+    }
+    Ok(written)
+}
+
+/// Declares the module of `day` in the library, in order, and puts its
+/// solution in its slot of the registry
+fn register(lib: &str, day: u8) -> std::result::Result<String, String> {
+    let mut lines: Vec<String> = lib.lines().map(str::to_owned).collect();
+    let declaration = format!("pub mod day_{day};");
+    if !lines.contains(&declaration) {
+        let modules = lines
+            .iter()
+            .position(|line| line.starts_with("pub mod "))
+            .ok_or("no modules declared")?;
+        let mut at = lines[modules..]
+            .iter()
+            .position(|line| !line.starts_with("pub mod ") && !line.starts_with("#["))
+            .map_or(lines.len(), |end| modules + end);
+        // Before the first module that sorts after it, and its attributes
+        let name = format!("day_{day}");
+        let sorts_after = |line: &str| {
+            line.strip_prefix("pub mod ")
+                .and_then(|line| line.strip_suffix(';'))
+                .is_some_and(|other| other > name.as_str())
+        };
+        if let Some(after) = (modules..at).find(|&i| sorts_after(&lines[i])) {
+            at = after;
+            while at > modules && lines[at - 1].starts_with("#[") {
+                at -= 1;
+            }
+        }
+        lines.insert(at, declaration);
+    }
+
+    let registry = lines
+        .iter()
+        .position(|line| line.starts_with("pub static DAYS"))
+        .ok_or("no `DAYS` registry")?;
+    let slots: Vec<usize> = (registry + 1..lines.len())
+        .take_while(|&i| lines[i].trim() != "];")
+        .filter(|&i| lines[i].trim().starts_with('&'))
+        .collect();
+    let &slot = slots
+        .get(day as usize - 1)
+        .ok_or(format!("no slot for day {day} in `DAYS`"))?;
+    lines[slot] = format!("    &day_{day}::Day{day},");
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds the benchmark of `day` to the group of every day, in order
+fn bench(benches: &str, day: u8) -> std::result::Result<String, String> {
+    let entry = format!("    bench_day::<day_{day}::Day{day}>,");
+    let mut lines: Vec<String> = benches.lines().map(str::to_owned).collect();
+    if lines.contains(&entry) {
+        return Ok(benches.to_owned());
+    }
+    let entries: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let day = line.trim().strip_prefix("bench_day::<day_")?;
+            Some((i, day.split("::").next()?.parse().ok()?))
+        })
+        .collect();
+    let at = match entries.iter().find(|&&(_, other)| other > day) {
+        Some(&(i, _)) => i,
+        None => {
+            let group = lines
+                .iter()
+                .position(|line| line.starts_with("criterion_group!("))
+                .ok_or("no `criterion_group!`")?;
+            // After the name of the group, or after the last day
+            entries.last().map_or(group + 2, |&(i, _)| i + 1)
+        }
+    };
+    lines.insert(at, entry);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn read(path: &Path) -> Result<String> {
+    read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn save(path: &Path, text: &str) -> Result<()> {
+    write(path, text).map_err(|source| Error::Io {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn invalid(path: &Path, reason: String) -> Error {
+    Error::Invalid {
+        path: path.to_owned(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_are_registered_in_order() {
+        let lib = "pub mod day_1;\npub mod day_20;\npub mod day_3;\n#[cfg(feature = \"x\")]\npub mod x;\n\n\
+                   pub static DAYS: [&dyn Solver; 3] = [\n    &day_1::Day1,\n    &Unsolved,\n    \
+                   &day_3::Day3,\n];\n";
+        assert_eq!(
+            register(lib, 2).unwrap(),
+            "pub mod day_1;\npub mod day_2;\npub mod day_20;\npub mod day_3;\n#[cfg(feature = \"x\")]\npub mod x;\n\n\
+             pub static DAYS: [&dyn Solver; 3] = [\n    &day_1::Day1,\n    &day_2::Day2,\n    \
+             &day_3::Day3,\n];\n"
+        );
+        assert!(register(lib, 4).is_err());
+
+        let benches = "criterion_group!(\n    days,\n    bench_day::<day_1::Day1>,\n    \
+                       bench_day::<day_3::Day3>,\n);\n";
+        assert_eq!(
+            bench(benches, 2).unwrap(),
+            "criterion_group!(\n    days,\n    bench_day::<day_1::Day1>,\n    \
+             bench_day::<day_2::Day2>,\n    bench_day::<day_3::Day3>,\n);\n"
+        );
+        assert_eq!(bench(benches, 3).unwrap(), benches);
+        assert!(bench(benches, 4)
+            .unwrap()
+            .contains("Day3>,\n    bench_day::<day_4::Day4>,\n);"));
+    }
+}
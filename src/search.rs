@@ -0,0 +1,312 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use log::debug;
+
+/// What a search found out about the states it reached: their distance from
+/// the closest start, and every predecessor they have on an optimal path
+pub struct Explored<S, C> {
+    distances: HashMap<S, C>,
+    predecessors: HashMap<S, Vec<S>>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> Explored<S, C> {
+    fn new() -> Self {
+        Explored {
+            distances: HashMap::new(),
+            predecessors: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, state: &S) -> Option<C> {
+        self.distances.get(state).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<S, C> {
+        &self.distances
+    }
+
+    /// States right before `state` on its optimal paths, none for the starts
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.predecessors.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    /// One optimal path from a start to `goal`, both included
+    pub fn path_to(&self, goal: &S) -> Option<Vec<S>> {
+        self.distances.get(goal)?;
+        let mut path = vec![goal.clone()];
+        while let Some(previous) = self.predecessors(path.last().unwrap()).first() {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every state on any optimal path to one of the `goals`
+    pub fn on_optimal_paths(&self, goals: impl IntoIterator<Item = S>) -> HashSet<S> {
+        let mut seen = HashSet::new();
+        let mut queue: Vec<S> = goals
+            .into_iter()
+            .filter(|goal| self.distances.contains_key(goal))
+            .collect();
+        while let Some(state) = queue.pop() {
+            if seen.insert(state.clone()) {
+                queue.extend(self.predecessors(&state).iter().cloned());
+            }
+        }
+        seen
+    }
+
+    /// Number of distinct optimal paths from the starts to `goal`
+    pub fn count_paths_to(&self, goal: &S) -> usize {
+        fn count<S: Clone + Eq + Hash, C: Copy>(
+            explored: &Explored<S, C>,
+            state: &S,
+            counts: &mut HashMap<S, usize>,
+        ) -> usize {
+            if let Some(&known) = counts.get(state) {
+                return known;
+            }
+            let total = match explored.predecessors(state) {
+                [] => 1,
+                previous => previous.iter().map(|p| count(explored, p, counts)).sum(),
+            };
+            counts.insert(state.clone(), total);
+            total
+        }
+
+        match self.distances.contains_key(goal) {
+            true => count(self, goal, &mut HashMap::new()),
+            false => 0,
+        }
+    }
+
+    /// Records reaching `next` from `state` at `cost`, returning whether it is
+    /// the best way there found so far
+    fn relax(&mut self, state: &S, next: S, cost: C) -> bool
+    where
+        C: Ord,
+    {
+        match self.distances.get(&next) {
+            Some(&known) if cost > known => false,
+            Some(&known) if cost == known => {
+                let previous = self.predecessors.entry(next).or_default();
+                if !previous.contains(state) {
+                    previous.push(state.clone());
+                }
+                false
+            }
+            _ => {
+                self.distances.insert(next.clone(), cost);
+                self.predecessors.insert(next, vec![state.clone()]);
+                true
+            }
+        }
+    }
+}
+
+/// Breadth-first search from `starts`, where every move costs one step. The
+/// search stops once the states as far as the first goal are all reached; pass
+/// `|_| false` to explore everything reachable.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Explored<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut explored = Explored::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        explored.distances.insert(start.clone(), 0);
+        queue.push_back(start);
+    }
+
+    let (mut goal_distance, mut widest) = (None, queue.len());
+    while let Some(state) = queue.pop_front() {
+        let distance = explored.distances[&state];
+        if goal_distance.is_some_and(|goal_distance| distance >= goal_distance) {
+            break;
+        }
+        if goal(&state) {
+            goal_distance = Some(distance);
+            continue;
+        }
+        for next in successors(&state) {
+            if explored.relax(&state, next.clone(), distance + 1) {
+                queue.push_back(next);
+            }
+        }
+        widest = widest.max(queue.len());
+    }
+    debug!(
+        "bfs reached {} states, with up to {widest} in the frontier",
+        explored.distances.len()
+    );
+    explored
+}
+
+/// Dijkstra's search from `starts`, with `successors` giving each next state
+/// and the cost of moving there. Costs must be positive. The search stops
+/// once every state as cheap as the first goal is settled, so ties between
+/// goals and their optimal predecessors are all kept.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Explored<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut explored = Explored::new();
+    // The heap orders indices into `states`, so that states need no ordering
+    let mut states = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        explored.distances.insert(start.clone(), C::default());
+        heap.push(Reverse((C::default(), states.len())));
+        states.push(start);
+    }
+
+    let (mut goal_cost, mut widest) = (None, heap.len());
+    while let Some(Reverse((cost, index))) = heap.pop() {
+        if goal_cost.is_some_and(|goal_cost| cost > goal_cost) {
+            break;
+        }
+        let state = states[index].clone();
+        if explored.distances[&state] < cost {
+            continue;
+        }
+        if goal(&state) {
+            goal_cost = Some(cost);
+            continue;
+        }
+        for (next, step) in successors(&state) {
+            if explored.relax(&state, next.clone(), cost + step) {
+                heap.push(Reverse((cost + step, states.len())));
+                states.push(next);
+            }
+        }
+        widest = widest.max(heap.len());
+    }
+    debug!(
+        "dijkstra reached {} states, with up to {widest} in the frontier",
+        explored.distances.len()
+    );
+    explored
+}
+
+/// A* search for the cheapest path from `start` to a goal, guided by a
+/// `heuristic` that must never overestimate the remaining cost. Returns the
+/// path, both ends included, and its cost.
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut explored = Explored::new();
+    explored.distances.insert(start.clone(), C::default());
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut states = vec![start];
+
+    let mut widest = 1;
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let state = states[index].clone();
+        if explored.distances[&state] < cost {
+            continue;
+        }
+        if goal(&state) {
+            debug!(
+                "astar reached {} states, with up to {widest} in the frontier",
+                explored.distances.len()
+            );
+            return Some((explored.path_to(&state).unwrap(), cost));
+        }
+        for (next, step) in successors(&state) {
+            if explored.relax(&state, next.clone(), cost + step) {
+                heap.push(Reverse((
+                    cost + step + heuristic(&next),
+                    cost + step,
+                    states.len(),
+                )));
+                states.push(next);
+            }
+        }
+        widest = widest.max(heap.len());
+    }
+    debug!(
+        "astar found no path after {} states",
+        explored.distances.len()
+    );
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A diamond: 0 -> 1 -> 3 and 0 -> 2 -> 3, then 3 -> 4
+    fn diamond(state: &u32) -> Vec<(u32, u32)> {
+        match state {
+            0 => vec![(1, 1), (2, 1)],
+            1 | 2 => vec![(3, 1)],
+            3 => vec![(4, 5)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn bfs_keeps_every_optimal_predecessor() {
+        let successors = |state: &u32| diamond(state).into_iter().map(|(next, _)| next);
+        let explored = bfs([0], successors, |_| false);
+        assert_eq!(explored.distance(&4), Some(3));
+        assert_eq!(explored.predecessors(&3), [1, 2]);
+        assert_eq!(explored.count_paths_to(&4), 2);
+        assert_eq!(explored.path_to(&4), Some(vec![0, 1, 3, 4]));
+        assert_eq!(explored.on_optimal_paths([3]), HashSet::from([0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn bfs_stops_at_the_goal() {
+        let successors = |state: &u32| diamond(state).into_iter().map(|(next, _)| next);
+        let explored = bfs([0], successors, |&state| state == 3);
+        assert_eq!(explored.distance(&3), Some(2));
+        assert_eq!(explored.distance(&4), None);
+    }
+
+    #[test]
+    fn dijkstra_weighs_moves() {
+        let successors = |&state: &u32| match state {
+            0 => vec![(1, 10), (2, 1)],
+            2 => vec![(1, 2)],
+            _ => vec![],
+        };
+        let explored = dijkstra([0], successors, |_| false);
+        assert_eq!(explored.distance(&1), Some(3));
+        assert_eq!(explored.path_to(&1), Some(vec![0, 2, 1]));
+        assert_eq!(explored.distance(&7), None);
+        assert_eq!(explored.path_to(&7), None);
+    }
+
+    #[test]
+    fn astar_finds_the_cheapest_path() {
+        let (path, cost) = astar(0, diamond, |_| 0, |&state| state == 4).unwrap();
+        assert_eq!(path, [0, 1, 3, 4]);
+        assert_eq!(cost, 7);
+        assert!(astar(4, diamond, |_| 0, |&state| state == 0).is_none());
+    }
+}
@@ -0,0 +1,390 @@
+use std::{
+    io::Read,
+    net::SocketAddr,
+    panic::catch_unwind,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{
+    params::{Param, Params},
+    report::Record,
+    Error, Part, Result, Solved, DAYS,
+};
+
+/// How much a request may ask of the server
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Largest puzzle input accepted, in bytes
+    pub max_body: usize,
+    /// How long a solver may run before its request is answered with an error
+    pub timeout: Duration,
+    /// Solvers that may run at once, counting the ones given up on, which
+    /// cannot be stopped and run on until they finish
+    pub solvers: usize,
+}
+
+/// An HTTP API over the solvers:
+///
+/// - `POST /day/{n}/part/{p}` solves the puzzle input in the body, with the
+///   parameters in the query string, such as `?width=7&height=7`, and answers
+///   with a [`Record`]. Names and values may be percent-encoded.
+/// - `GET /days` lists the days and their parameters.
+pub struct Service {
+    server: Server,
+    limits: Limits,
+    running: Arc<AtomicUsize>,
+}
+
+/// What a request asks for
+#[derive(Debug, PartialEq, Eq)]
+enum Route {
+    Days,
+    Solve { day: u8, part: Part, params: Params },
+}
+
+/// A request that cannot be served, with its HTTP status
+#[derive(Debug, PartialEq, Eq)]
+struct Refusal {
+    status: u16,
+    reason: String,
+}
+
+impl Refusal {
+    fn new(status: u16, reason: impl Into<String>) -> Self {
+        Refusal {
+            status,
+            reason: reason.into(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Day {
+    day: u8,
+    params: &'static [Param],
+}
+
+impl Service {
+    /// Listens on `address`, such as `127.0.0.1:8024`
+    pub fn bind(address: &str, limits: Limits) -> Result<Service> {
+        let server = Server::http(address).map_err(|error| Error::Serve {
+            address: address.to_owned(),
+            reason: error.to_string(),
+        })?;
+        Ok(Service {
+            server,
+            limits,
+            running: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// The address listened on, with the port picked when binding to port 0
+    pub fn address(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Answers requests, each on its own thread, until the process ends
+    pub fn run(&self) {
+        thread::scope(|scope| {
+            for request in self.server.incoming_requests() {
+                scope.spawn(|| self.respond(request));
+            }
+        });
+    }
+
+    fn respond(&self, mut request: Request) {
+        let start = Instant::now();
+        let (status, body) = match self.handle(&mut request) {
+            Ok((status, body)) => (status, body),
+            Err(refusal) => (
+                refusal.status,
+                serde_json::json!({ "error": refusal.reason }).to_string(),
+            ),
+        };
+        let url = request.url().to_owned();
+        info!(
+            "{} {url} answered {status} in {:.2?}",
+            request.method(),
+            start.elapsed()
+        );
+        let json = Header::from_bytes("Content-Type", "application/json").unwrap();
+        let response = Response::from_string(body + "\n")
+            .with_status_code(status)
+            .with_header(json);
+        if let Err(error) = request.respond(response) {
+            warn!("unable to answer {url}: {error}");
+        }
+    }
+
+    fn handle(&self, request: &mut Request) -> std::result::Result<(u16, String), Refusal> {
+        match route(request.method(), request.url())? {
+            Route::Days => {
+                let days: Vec<Day> = DAYS
+                    .iter()
+                    .map(|solver| Day {
+                        day: solver.day(),
+                        params: solver.params(),
+                    })
+                    .collect();
+                Ok((200, serde_json::to_string(&days).unwrap()))
+            }
+            Route::Solve { day, part, params } => {
+                let input = self.read_body(request)?;
+                let solved = self.solve(day, part, input, params)?;
+                let status = match &solved {
+                    Ok(_) => 200,
+                    Err(Error::TimedOut { .. }) => 504,
+                    Err(_) => 422,
+                };
+                let record = Record::of_run(day, Path::new("<request>"), &[part], &solved);
+                Ok((status, record[0].to_json()))
+            }
+        }
+    }
+
+    fn read_body(&self, request: &mut Request) -> std::result::Result<String, Refusal> {
+        let max_body = self.limits.max_body;
+        let too_large = || Refusal::new(413, format!("inputs are limited to {max_body} bytes"));
+        if request
+            .body_length()
+            .is_some_and(|length| length > max_body)
+        {
+            return Err(too_large());
+        }
+        // Chunked bodies do not say how long they are
+        let mut body = Vec::new();
+        request
+            .as_reader()
+            .take(max_body as u64 + 1)
+            .read_to_end(&mut body)
+            .map_err(|error| Refusal::new(400, format!("unable to read the input: {error}")))?;
+        if body.len() > max_body {
+            return Err(too_large());
+        }
+        String::from_utf8(body).map_err(|_| Refusal::new(400, "the input is not UTF-8"))
+    }
+
+    /// Solves on a thread of its own, giving up on it after the timeout
+    fn solve(
+        &self,
+        day: u8,
+        part: Part,
+        input: String,
+        params: Params,
+    ) -> std::result::Result<Result<Solved>, Refusal> {
+        let running = Arc::clone(&self.running);
+        if running.fetch_add(1, Ordering::SeqCst) >= self.limits.solvers {
+            running.fetch_sub(1, Ordering::SeqCst);
+            return Err(Refusal::new(
+                503,
+                "too many puzzles being solved, retry later",
+            ));
+        }
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let solved =
+                catch_unwind(|| DAYS[day as usize - 1].solve_timed(&input, &[part], &params))
+                    .unwrap_or_else(|payload| Err(Error::panicked(payload)));
+            running.fetch_sub(1, Ordering::SeqCst);
+            // Nobody listens any more when the request timed out
+            let _ = sender.send(solved);
+        });
+        let limit = self.limits.timeout;
+        Ok(receiver
+            .recv_timeout(limit)
+            .unwrap_or(Err(Error::TimedOut { limit })))
+    }
+}
+
+fn route(method: &Method, url: &str) -> std::result::Result<Route, Refusal> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let not_found = || Refusal::new(404, format!("no such resource `{path}`"));
+    let route = match segments[..] {
+        ["days"] => Route::Days,
+        ["day", day, "part", part] => {
+            let day = day.parse().ok().filter(|day| (1..=25).contains(day));
+            let part = match part {
+                "1" => Some(Part::One),
+                "2" => Some(Part::Two),
+                _ => None,
+            };
+            let (Some(day), Some(part)) = (day, part) else {
+                return Err(not_found());
+            };
+            let mut params = Params::default();
+            for assignment in query.split('&').filter(|a| !a.is_empty()) {
+                let (name, value) = Params::parse_assignment(assignment)
+                    .map_err(|reason| Refusal::new(400, reason))?;
+                let decode = |text: &str| {
+                    percent_decode(text).ok_or_else(|| {
+                        Refusal::new(400, format!("`{text}` is not percent-encoded UTF-8"))
+                    })
+                };
+                params.set(decode(&name)?, decode(&value)?);
+            }
+            Route::Solve { day, part, params }
+        }
+        _ => return Err(not_found()),
+    };
+    let allowed = match route {
+        Route::Days => Method::Get,
+        Route::Solve { .. } => Method::Post,
+    };
+    match *method == allowed {
+        true => Ok(route),
+        false => Err(Refusal::new(
+            405,
+            format!("`{path}` only accepts {allowed}"),
+        )),
+    }
+}
+
+/// Replaces each `%` and the two hex digits after it by the byte they stand
+/// for
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+        let hex = rest
+            .get(..2)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+        bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+        rest = &rest[2..];
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+    };
+
+    use super::*;
+
+    #[test]
+    fn urls_route_to_solvers() {
+        assert_eq!(route(&Method::Get, "/days"), Ok(Route::Days));
+        let mut params = Params::default();
+        params.set("width", "7");
+        params.set("fallen", "12");
+        assert_eq!(
+            route(&Method::Post, "/day/18/part/2?width=7&fallen=12"),
+            Ok(Route::Solve {
+                day: 18,
+                part: Part::Two,
+                params
+            })
+        );
+        let mut params = Params::default();
+        params.set("carry_out", "z45");
+        assert_eq!(
+            route(&Method::Post, "/day/24/part/2?carry_out=z%345"),
+            Ok(Route::Solve {
+                day: 24,
+                part: Part::Two,
+                params
+            })
+        );
+        for (method, url, status) in [
+            (Method::Get, "/day/1/part/1", 405),
+            (Method::Post, "/days", 405),
+            (Method::Post, "/day/26/part/1", 404),
+            (Method::Post, "/day/1/part/3", 404),
+            (Method::Post, "/day/1", 404),
+            (Method::Post, "/day/1/part/1?width", 400),
+            (Method::Post, "/day/1/part/1?width=%7", 400),
+            (Method::Post, "/day/1/part/1?width=%ff", 400),
+        ] {
+            assert_eq!(route(&method, url).unwrap_err().status, status, "{url}");
+        }
+    }
+
+    /// Sends a request and returns the status and body of the response
+    fn send(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+             Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        (status, body.trim().to_owned())
+    }
+
+    #[test]
+    fn answers_over_http() {
+        let limits = Limits {
+            max_body: 64,
+            timeout: Duration::from_secs(10),
+            solvers: 2,
+        };
+        let service = Service::bind("127.0.0.1:0", limits).unwrap();
+        let address = service.address().unwrap();
+        thread::spawn(move || service.run());
+
+        let (status, body) = send(address, "POST", "/day/1/part/1", "1   3\n2   5\n");
+        assert_eq!(status, 200);
+        assert!(body.contains(r#""answer":"5""#), "{body}");
+        assert!(body.contains(r#""success":true"#), "{body}");
+
+        let (status, body) = send(address, "POST", "/day/1/part/1", "3   x\n");
+        assert_eq!(status, 422);
+        assert!(body.contains(r#""success":false"#), "{body}");
+
+        let (status, _) = send(address, "POST", "/day/1/part/1", &"1   2\n".repeat(20));
+        assert_eq!(status, 413);
+
+        let (status, body) = send(address, "GET", "/days", "");
+        assert_eq!(status, 200);
+        assert!(
+            body.contains(r#"{"day":18,"params":[{"name":"width""#),
+            "{body}"
+        );
+    }
+
+    #[test]
+    fn slow_solvers_time_out() {
+        let limits = Limits {
+            max_body: 64,
+            timeout: Duration::from_millis(1),
+            solvers: 1,
+        };
+        let service = Service::bind("127.0.0.1:0", limits).unwrap();
+        let address = service.address().unwrap();
+        thread::spawn(move || service.run());
+
+        // Far more rounds than a millisecond allows, so the solver is still
+        // running on when the next request comes
+        let slow = "/day/22/part/1?rounds=1000000000";
+        let (status, body) = send(address, "POST", slow, "1\n");
+        assert_eq!(status, 504);
+        assert!(body.contains(r#""success":false"#), "{body}");
+
+        let (status, body) = send(address, "POST", "/day/1/part/1", "1   3\n");
+        assert_eq!(status, 503);
+        assert!(body.contains("retry later"), "{body}");
+    }
+}
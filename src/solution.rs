@@ -0,0 +1,257 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use log::info;
+
+use crate::{
+    animation::Recorder,
+    arith::catch_overflow,
+    bench::{self, Measurement, Sampling},
+    error::Result,
+    generate::{Generated, Generator},
+    params::{Param, Params},
+    render::Canvas,
+};
+
+/// The answer to one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    /// The puzzle has no answer for this part
+    None,
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{number}"),
+            Answer::Text(text) => write!(f, "{text}"),
+            Answer::None => write!(f, "none"),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($number:ty),*) => {
+        $(impl From<$number> for Answer {
+            fn from(value: $number) -> Self {
+                Answer::Number(value as i128)
+            }
+        })*
+    };
+}
+
+answer_from_number!(u8, u16, u32, u64, usize, i8, i16, i32, i64);
+
+/// Big integers that do not fit in a number are answered as text
+#[cfg(feature = "bigint")]
+macro_rules! answer_from_big {
+    ($($number:ty),*) => {
+        $(impl From<$number> for Answer {
+            fn from(value: $number) -> Self {
+                match i128::try_from(&value) {
+                    Ok(number) => Answer::Number(number),
+                    Err(_) => Answer::Text(value.to_string()),
+                }
+            }
+        })*
+    };
+}
+
+#[cfg(feature = "bigint")]
+answer_from_big!(num_bigint::BigInt, num_bigint::BigUint);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_owned())
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => Answer::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+/// Answers to some parts of a puzzle, with how long each step took
+#[derive(Debug, Clone)]
+pub struct Solved {
+    pub parse: Duration,
+    pub parts: Vec<(Part, Answer, Duration)>,
+}
+
+/// A solver for the two parts of a day's puzzle
+pub trait Solution {
+    const DAY: u8;
+
+    /// The typed model of the puzzle input, shared by both parts
+    type Input;
+
+    /// Puzzle constants that are not part of the input, with their defaults
+    const PARAMS: &'static [Param] = &[];
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    /// Parses the input of a puzzle whose [`Solution::PARAMS`] take the given
+    /// values. Days with parameters keep them in their input.
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        let _ = params;
+        Self::parse(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer;
+
+    fn part_2(input: &Self::Input) -> Answer;
+
+    /// Makes up a random input in the format of the puzzle, that both parts
+    /// can solve
+    fn generate(generator: &mut Generator) -> Generated;
+
+    /// A picture of how a part gets solved, for the days that can draw one
+    fn render(input: &Self::Input, part: Part) -> Option<Canvas> {
+        let _ = (input, part);
+        None
+    }
+
+    /// Draws a frame per step of the simulation behind a part, returning
+    /// whether the day has one to draw
+    fn animate(input: &Self::Input, part: Part, recorder: &mut Recorder) -> bool {
+        let _ = (input, part, recorder);
+        false
+    }
+}
+
+/// Object-safe view of a [`Solution`], used to keep every day in one registry
+pub trait Solver: Sync {
+    fn day(&self) -> u8;
+
+    fn params(&self) -> &'static [Param];
+
+    /// Parses the input once and solves each of the given parts, with
+    /// `params` overriding the defaults of the day's parameters. Overflows
+    /// of checked arithmetic are errors.
+    fn solve(&self, input: &str, parts: &[Part], params: &Params) -> Result<Vec<Answer>>;
+
+    /// Like [`Solver::solve`], timing parsing and each part once
+    fn solve_timed(&self, input: &str, parts: &[Part], params: &Params) -> Result<Solved>;
+
+    /// Draws how the given part gets solved, if the day can
+    fn render(&self, input: &str, part: Part, params: &Params) -> Result<Option<Canvas>>;
+
+    /// Records the simulation behind the given part, if the day has one
+    fn animate(
+        &self,
+        input: &str,
+        part: Part,
+        params: &Params,
+        recorder: &mut Recorder,
+    ) -> Result<bool>;
+
+    /// Makes up a random input of about `size`, the same for the same `seed`
+    fn generate(&self, seed: u64, size: usize) -> Generated;
+
+    /// Times parsing and each part separately
+    fn bench(&self, input: &str, params: &Params, sampling: &Sampling) -> Result<Measurement>;
+}
+
+impl<S: Solution + Sync> Solver for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn params(&self) -> &'static [Param] {
+        S::PARAMS
+    }
+
+    fn solve(&self, input: &str, parts: &[Part], params: &Params) -> Result<Vec<Answer>> {
+        let input = S::parse_with(input, &params.resolve(S::PARAMS)?)?;
+        parts
+            .iter()
+            .map(|part| {
+                catch_overflow(|| match part {
+                    Part::One => S::part_1(&input),
+                    Part::Two => S::part_2(&input),
+                })
+            })
+            .collect()
+    }
+
+    fn solve_timed(&self, input: &str, parts: &[Part], params: &Params) -> Result<Solved> {
+        let params = params.resolve(S::PARAMS)?;
+        let start = Instant::now();
+        let input = S::parse_with(input, &params)?;
+        let parse = start.elapsed();
+        info!("parsed day {} in {parse:.2?}", S::DAY);
+        let parts = parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let answer = catch_overflow(|| match part {
+                    Part::One => S::part_1(&input),
+                    Part::Two => S::part_2(&input),
+                })?;
+                let elapsed = start.elapsed();
+                info!(
+                    "solved day {} part {} in {elapsed:.2?}",
+                    S::DAY,
+                    part.number()
+                );
+                Ok((part, answer, elapsed))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Solved { parse, parts })
+    }
+
+    fn render(&self, input: &str, part: Part, params: &Params) -> Result<Option<Canvas>> {
+        let input = S::parse_with(input, &params.resolve(S::PARAMS)?)?;
+        Ok(S::render(&input, part))
+    }
+
+    fn animate(
+        &self,
+        input: &str,
+        part: Part,
+        params: &Params,
+        recorder: &mut Recorder,
+    ) -> Result<bool> {
+        let input = S::parse_with(input, &params.resolve(S::PARAMS)?)?;
+        Ok(S::animate(&input, part, recorder))
+    }
+
+    fn generate(&self, seed: u64, size: usize) -> Generated {
+        S::generate(&mut Generator::new(seed, size))
+    }
+
+    fn bench(&self, input: &str, params: &Params, sampling: &Sampling) -> Result<Measurement> {
+        bench::measure::<S>(input, params, sampling)
+    }
+}
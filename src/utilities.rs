@@ -1,13 +1,6 @@
-use std::path::Path;
-use std::{
-    fs::File,
-    io::{self, BufRead},
-};
-
-pub fn read_lines<P>(file_name: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(file_name)?;
-    Ok(io::BufReader::new(file).lines())
+/// Reads a variant of a day's input, such as `test` or `test.small_1`, from
+/// the input folder
+#[cfg(test)]
+pub(crate) fn read_example(day: u8, variant: &str) -> String {
+    crate::input::Input::variant(day, variant).read().unwrap()
 }